@@ -1,10 +1,11 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use matches::assert_matches;
 use ray_tracer::{
-    canvas_to_ppm, intersect, lighting, normalize, point, vector, Canvas, Color, Computations,
-    Intersection, Material, Matrix, Matrix4, PointLight, Ray, Sphere, World,
+    canvas_to_ppm, intersect, lighting, normalize, point, scaling, translation, vector, write_ppm,
+    BitDepth, Canvas, Color, Computations, Intersection, Intersections, Matrix, Matrix4,
+    OutputTransform, PointLight, PpmFormat, Ray, Shape, Sphere, World, MAX_REFLECTION_DEPTH,
 };
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
 pub fn hit(c: &mut Criterion) {
     let s = Sphere::default();
@@ -17,7 +18,7 @@ pub fn hit(c: &mut Criterion) {
                 origin: ray_origin,
                 direction: normalize(&(position - ray_origin)),
             };
-            let mut xs = intersect(Rc::new(s), &r).unwrap();
+            let mut xs = intersect(Rc::new(s.clone()), &r).unwrap();
             xs.hit();
         })
     });
@@ -35,11 +36,9 @@ pub fn computations(c: &mut Criterion) {
                         origin: point(0.0, 0.0, t.0),
                         direction: vector(0.0, 0.0, 1.0),
                     };
-                    let i = Intersection {
-                        t: t.1,
-                        object: RefCell::new(Rc::new(s)),
-                    };
-                    Computations::prepare_computation(i, r).unwrap();
+                    let i = Intersection::new(t.1, Rc::new(s.clone()));
+                    Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i]))
+                        .unwrap();
                 })
             },
         );
@@ -84,10 +83,22 @@ pub fn canvas(c: &mut Criterion) {
             canvas_to_ppm(&canvas);
         })
     });
+    c.bench_function("Streaming canvas as binary PPM", |b| {
+        b.iter(|| {
+            write_ppm(
+                &canvas,
+                PpmFormat::Raw,
+                BitDepth::Eight,
+                &OutputTransform::default(),
+                &mut std::io::sink(),
+            )
+            .unwrap();
+        })
+    });
 }
 
 pub fn reflections(c: &mut Criterion) {
-    let m = Material::default();
+    let object = Sphere::default();
     let position = point(0.0, 0.0, 0.0);
     let eyev = vector(0.0, 0.0, -1.0);
     let normalv = vector(0.0, 0.0, -1.0);
@@ -97,24 +108,91 @@ pub fn reflections(c: &mut Criterion) {
     };
     c.bench_function("Reflections on light using Phong model", |b| {
         b.iter(|| {
-            lighting(&m, light, position, eyev, normalv);
+            lighting(
+                &object,
+                Matrix4::identity_matrix(),
+                light,
+                position,
+                eyev,
+                normalv,
+                false,
+            )
+            .unwrap();
         })
     });
 }
 
 #[allow(unused_variables)]
 pub fn world_intersections(c: &mut Criterion) {
-    let mut w = World::default();
+    let w = World::default();
     let r = Ray {
         origin: point(0.0, 0.0, -5.0),
         direction: vector(0.0, 0.0, 1.0),
     };
     let result_color = Color::new(0.38066, 0.47583, 0.2855);
     c.bench_function("Color at object inside World when ray hits", |b| {
-        b.iter(|| assert_matches!(w.color_at(&r), Ok(result_color)))
+        b.iter(|| assert_matches!(w.color_at(&r, MAX_REFLECTION_DEPTH), Ok(result_color)))
     });
 }
 
+/// Grid of small spheres - without the hierarchy every ray is tested against every sphere
+fn many_spheres_world() -> World {
+    let mut w = World {
+        light: Some(PointLight {
+            position: point(-10.0, 10.0, -10.0),
+            intensity: Color::new(1.0, 1.0, 1.0),
+        }),
+        objects: vec![],
+        bvh: None,
+    };
+    for x in 0..10 {
+        for y in 0..10 {
+            for z in 0..4 {
+                let mut s = Sphere::default();
+                s.set_transform(
+                    translation(x as f64 - 4.5, y as f64 - 4.5, z as f64) * scaling(0.3, 0.3, 0.3),
+                );
+                w.objects.push(Rc::new(s.clone()));
+            }
+        }
+    }
+    w
+}
+
+pub fn bounding_volume_hierarchy(c: &mut Criterion) {
+    let flat = many_spheres_world();
+    let mut divided = many_spheres_world();
+    divided.build_bvh(4);
+    let rays = [
+        (point(0.5, 0.5, -5.0), vector(0.0, 0.0, 1.0)),
+        (point(0.2, 0.0, -5.0), vector(0.1, 0.2, 1.0)),
+        (point(20.0, 0.0, -5.0), vector(0.0, 0.0, 1.0)),
+    ]
+    .iter()
+    .map(|(origin, direction)| Ray {
+        origin: *origin,
+        direction: normalize(direction),
+    })
+    .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("Color at in world with 400 spheres");
+    group.sample_size(20);
+    group.bench_function(BenchmarkId::new("flat", ""), |b| {
+        b.iter(|| {
+            for r in &rays {
+                flat.color_at(r, MAX_REFLECTION_DEPTH).unwrap();
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("bvh", ""), |b| {
+        b.iter(|| {
+            for r in &rays {
+                divided.color_at(r, MAX_REFLECTION_DEPTH).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
 criterion_group!(
     benchmark,
     hit,
@@ -122,6 +200,7 @@ criterion_group!(
     matrix,
     canvas,
     reflections,
-    world_intersections
+    world_intersections,
+    bounding_volume_hierarchy
 );
 criterion_main!(benchmark);
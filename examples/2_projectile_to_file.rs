@@ -1,6 +1,7 @@
 use ray_tracer::TupleT;
 use ray_tracer::{
-    canvas_to_ppm, eq_with_eps, normalize, point, save, vector, Canvas, Color, Tuple,
+    eq_with_eps, normalize, point, save_png, vector, BitDepth, Canvas, Color, OutputTransform,
+    Tuple,
 };
 
 #[derive(Debug)]
@@ -50,6 +51,12 @@ fn main() {
             .unwrap();
         }
     }
-    let data = canvas_to_ppm(&c);
-    save(&data.get(), "saved.ppm").unwrap();
+    save_png(
+        &c,
+        "saved.png",
+        BitDepth::Eight,
+        None,
+        &OutputTransform::default(),
+    )
+    .unwrap();
 }
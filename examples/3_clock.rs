@@ -1,5 +1,5 @@
 use ray_tracer::TupleT;
-use ray_tracer::{canvas_to_ppm, point, rotation_z, save, Canvas, Color, PI};
+use ray_tracer::{point, rotation_z, save_png, BitDepth, Canvas, Color, OutputTransform, PI};
 
 fn main() {
     let mut c = Canvas::new(550, 550);
@@ -13,6 +13,12 @@ fn main() {
             .expect("Out of canvas border");
     }
 
-    let data = canvas_to_ppm(&c);
-    save(&data.get(), "saved.ppm").unwrap();
+    save_png(
+        &c,
+        "saved.png",
+        BitDepth::Eight,
+        None,
+        &OutputTransform::default(),
+    )
+    .unwrap();
 }
@@ -1,6 +1,6 @@
 use ray_tracer::{
-    canvas_to_ppm, intersect, normalize, point, save, scaling, shearing, Canvas, Color, Ray, Shape,
-    Sphere,
+    intersect, normalize, point, save_png, scaling, shearing, BitDepth, Canvas, Color,
+    OutputTransform, Ray, Shape, Sphere,
 };
 use std::rc::Rc;
 
@@ -12,12 +12,12 @@ const CANVAS_SIZE: usize = 100;
 /// axis needs to be at least 6x6 to fill whole sphere's shadow. wall_size = 7 is a precautions.
 /// Thanks to that, whole image scales with CANVAS_SIZE value - though larger images take
 /// exponentially longer to render.
-
 fn main() {
     let mut c = Canvas::new(CANVAS_SIZE, CANVAS_SIZE);
 
     let mut s = Sphere::default();
     s.set_transform(shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0) * scaling(0.5, 1.0, 1.0));
+    let s = Rc::new(s);
     let ray_origin = point(0.0, 0.0, -5.0);
     let wall_z = 10.0;
     let wall_size = 7.0;
@@ -34,7 +34,7 @@ fn main() {
                 origin: ray_origin,
                 direction: normalize(&(position - ray_origin)),
             };
-            let xs = intersect(Rc::new(s), &r).unwrap();
+            let xs = intersect(s.clone(), &r).unwrap();
             if !(*xs).is_empty() {
                 c.write_pixel(x, y, Color::new(0.85, 0.54, 0.48))
                     .expect("Out of canvas border");
@@ -42,6 +42,12 @@ fn main() {
         }
     }
 
-    let data = canvas_to_ppm(&c);
-    save(&data.get(), "saved.ppm").unwrap();
+    save_png(
+        &c,
+        "saved.png",
+        BitDepth::Eight,
+        None,
+        &OutputTransform::default(),
+    )
+    .unwrap();
 }
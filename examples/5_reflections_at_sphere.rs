@@ -1,57 +1,41 @@
 use ray_tracer::{
-    canvas_to_ppm, intersect, lighting, normalize, point, save, Canvas, Color, PointLight, Ray,
-    Shape, Sphere,
+    point, render, save_png, vector, view_transform, BitDepth, Camera, Color, OutputTransform,
+    PointLight, Shape, Sphere, World,
 };
 use std::rc::Rc;
 
 const CANVAS_SIZE: usize = 100;
 
+/// Same view as casting rays on a wall 7 units wide, 15 units in front of the eye - field of view
+/// is the angle that wall spans.
 fn main() {
-    let mut c = Canvas::new(CANVAS_SIZE, CANVAS_SIZE);
-
-    // light configuration - white light behind, above and to the left of the eye
-    let light = PointLight {
-        position: point(-10.0, 10.0, -10.0),
-        intensity: Color::new(1.0, 1.0, 1.0),
-    };
-
     let mut s = Sphere::default();
     s.set_color(Color::new(1.0, 0.2, 1.0));
 
-    let ray_origin = point(0.0, 0.0, -5.0);
-    let wall_z = 10.0;
-    let wall_size = 7.0;
-    let pixel_size = wall_size / CANVAS_SIZE as f64;
-    let half = wall_size / 2.0;
+    // light configuration - white light behind, above and to the left of the eye
+    let world = World {
+        light: Some(PointLight {
+            position: point(-10.0, 10.0, -10.0),
+            intensity: Color::new(1.0, 1.0, 1.0),
+        }),
+        objects: vec![Rc::new(s)],
+        bvh: None,
+    };
 
-    for y in 0..CANVAS_SIZE - 1 {
-        // world coordinates differs from regular, because originally 0,0 is at left bottom corner
-        let world_y = half - pixel_size * y as f64;
-        for x in 0..CANVAS_SIZE - 1 {
-            let world_x = -half + pixel_size * x as f64;
-            let position = point(world_x, world_y, wall_z);
-            let r = Ray {
-                origin: ray_origin,
-                direction: normalize(&(position - ray_origin)),
-            };
-            let mut xs = intersect(Rc::new(s), &r).unwrap();
-            let hit = xs.hit();
-            if let Some(hit) = hit {
-                let point = r.position(hit.t);
-                let normal = hit.object.borrow().normal_at(point).unwrap();
-                let eye = -r.direction;
-                let col = lighting(
-                    hit.object.borrow_mut().get_material(),
-                    light,
-                    point,
-                    eye,
-                    normal,
-                );
-                c.write_pixel(x, y, col).expect("Out of canvas border");
-            }
-        }
-    }
+    let mut camera = Camera::new(CANVAS_SIZE, CANVAS_SIZE, 2.0 * (3.5_f64 / 15.0).atan());
+    camera.set_transform(view_transform(
+        point(0.0, 0.0, -5.0),
+        point(0.0, 0.0, 0.0),
+        vector(0.0, 1.0, 0.0),
+    ));
 
-    let data = canvas_to_ppm(&c);
-    save(&data.get(), "saved.ppm").unwrap();
+    let c = render(&camera, &world).unwrap();
+    save_png(
+        &c,
+        "saved.png",
+        BitDepth::Eight,
+        None,
+        &OutputTransform::default(),
+    )
+    .unwrap();
 }
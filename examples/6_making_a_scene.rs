@@ -0,0 +1,94 @@
+use ray_tracer::{
+    point, render, rotation_x, rotation_y, save_png, scaling, translation, vector, view_transform,
+    BitDepth, Camera, Color, Material, OutputTransform, PointLight, Shape, Sphere, ToneMapping,
+    TransferFunction, World, PI,
+};
+use std::rc::Rc;
+
+const CANVAS_WIDTH: usize = 200;
+const CANVAS_HEIGHT: usize = 100;
+
+/// Floor and walls are extremely flattened spheres - they are placed so that they form a corner
+/// behind three spheres of different sizes.
+fn main() {
+    let mut floor = Sphere::default();
+    floor.set_transform(scaling(10.0, 0.01, 10.0));
+    floor.set_material(Material {
+        color: Color::new(1.0, 0.9, 0.9),
+        specular: 0.0,
+        ..Default::default()
+    });
+
+    let mut left_wall = Sphere::default();
+    left_wall.set_transform(
+        translation(0.0, 0.0, 5.0)
+            * rotation_y(-PI / 4.0)
+            * rotation_x(PI / 2.0)
+            * scaling(10.0, 0.01, 10.0),
+    );
+    left_wall.set_material(floor.get_material().clone());
+
+    let mut right_wall = Sphere::default();
+    right_wall.set_transform(
+        translation(0.0, 0.0, 5.0)
+            * rotation_y(PI / 4.0)
+            * rotation_x(PI / 2.0)
+            * scaling(10.0, 0.01, 10.0),
+    );
+    right_wall.set_material(floor.get_material().clone());
+
+    let mut middle = Sphere::default();
+    middle.set_transform(translation(-0.5, 1.0, 0.5));
+    middle.set_material(Material {
+        color: Color::new(0.1, 1.0, 0.5),
+        diffuse: 0.7,
+        specular: 0.3,
+        ..Default::default()
+    });
+
+    let mut right = Sphere::default();
+    right.set_transform(translation(1.5, 0.5, -0.5) * scaling(0.5, 0.5, 0.5));
+    right.set_material(Material {
+        color: Color::new(0.5, 1.0, 0.1),
+        diffuse: 0.7,
+        specular: 0.3,
+        ..Default::default()
+    });
+
+    let mut left = Sphere::default();
+    left.set_transform(translation(-1.5, 0.33, -0.75) * scaling(0.33, 0.33, 0.33));
+    left.set_material(Material {
+        color: Color::new(1.0, 0.8, 0.1),
+        diffuse: 0.7,
+        specular: 0.3,
+        ..Default::default()
+    });
+
+    // light configuration - white light above and to the left
+    let world = World {
+        light: Some(PointLight {
+            position: point(-10.0, 10.0, -10.0),
+            intensity: Color::new(1.0, 1.0, 1.0),
+        }),
+        objects: vec![
+            Rc::new(floor),
+            Rc::new(left_wall),
+            Rc::new(right_wall),
+            Rc::new(middle),
+            Rc::new(right),
+            Rc::new(left),
+        ],
+        bvh: None,
+    };
+
+    let mut camera = Camera::new(CANVAS_WIDTH, CANVAS_HEIGHT, PI / 3.0);
+    camera.set_transform(view_transform(
+        point(0.0, 1.5, -5.0),
+        point(0.0, 1.0, 0.0),
+        vector(0.0, 1.0, 0.0),
+    ));
+
+    let c = render(&camera, &world).unwrap();
+    let transform = OutputTransform::new(0.0, ToneMapping::AcesFilmic, TransferFunction::Srgb);
+    save_png(&c, "saved.png", BitDepth::Eight, None, &transform).unwrap();
+}
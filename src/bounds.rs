@@ -0,0 +1,397 @@
+use crate::{
+    matrix::Matrix4,
+    ray::Ray,
+    tuple::{point, Tuple, TupleT},
+    utils::EPSILON,
+};
+
+/// Axis-aligned bounding box. Box is empty when it doesn't contain any point - then its minimum
+/// is greater than its maximum
+#[derive(Debug, Copy, Clone)]
+pub struct BoundingBox {
+    min: Tuple,
+    max: Tuple,
+}
+
+/// Slab method - returns distances at which the ray crosses both planes perpendicular to a single
+/// axis, placed at min and max
+pub(crate) fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    // ray parallel to the slab never crosses it - multiplying by infinity keeps the sign of
+    // numerator, so the slab is either "always" or "never" entered
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl BoundingBox {
+    pub fn new(min: Tuple, max: Tuple) -> BoundingBox {
+        BoundingBox { min, max }
+    }
+
+    pub fn infinite() -> BoundingBox {
+        BoundingBox {
+            min: point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    pub fn get_min(&self) -> Tuple {
+        self.min
+    }
+
+    pub fn get_max(&self) -> Tuple {
+        self.max
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.get_x() > self.max.get_x()
+            || self.min.get_y() > self.max.get_y()
+            || self.min.get_z() > self.max.get_z()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [self.min, self.max]
+            .iter()
+            .all(|p| p.get_x().is_finite() && p.get_y().is_finite() && p.get_z().is_finite())
+    }
+
+    /// Grows the box so it contains given point
+    pub fn add_point(&mut self, p: Tuple) {
+        self.min = point(
+            self.min.get_x().min(p.get_x()),
+            self.min.get_y().min(p.get_y()),
+            self.min.get_z().min(p.get_z()),
+        );
+        self.max = point(
+            self.max.get_x().max(p.get_x()),
+            self.max.get_y().max(p.get_y()),
+            self.max.get_z().max(p.get_z()),
+        );
+    }
+
+    /// Grows the box so it contains the other box
+    pub fn add_box(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.add_point(other.min);
+            self.add_point(other.max);
+        }
+    }
+
+    pub fn contains_point(&self, p: Tuple) -> bool {
+        (self.min.get_x()..=self.max.get_x()).contains(&p.get_x())
+            && (self.min.get_y()..=self.max.get_y()).contains(&p.get_y())
+            && (self.min.get_z()..=self.max.get_z()).contains(&p.get_z())
+    }
+
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Box which contains this box after applying the transformation - all eight corners are
+    /// transformed and the new box is fitted around them
+    pub fn transform(&self, matrix: Matrix4) -> BoundingBox {
+        if self.is_empty() {
+            return *self;
+        }
+        let mut result = BoundingBox::default();
+        for &x in &[self.min.get_x(), self.max.get_x()] {
+            for &y in &[self.min.get_y(), self.max.get_y()] {
+                for &z in &[self.min.get_z(), self.max.get_z()] {
+                    let row = |r: usize| {
+                        // zero coefficients are skipped, so that infinite bounds aren't turned
+                        // into NaN (infinity * 0) by matrices which don't mix the axes
+                        [x, y, z, 1.0]
+                            .iter()
+                            .zip(matrix.0[r * 4..r * 4 + 4].iter())
+                            .filter(|(_, m)| **m != 0.0)
+                            .map(|(c, m)| c * m)
+                            .sum::<f64>()
+                    };
+                    let corner = point(row(0), row(1), row(2));
+                    if corner.get_x().is_nan() || corner.get_y().is_nan() || corner.get_z().is_nan()
+                    {
+                        return BoundingBox::infinite();
+                    }
+                    result.add_point(corner);
+                }
+            }
+        }
+        result
+    }
+
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        let (xtmin, xtmax) = check_axis(
+            ray.origin.get_x(),
+            ray.direction.get_x(),
+            self.min.get_x(),
+            self.max.get_x(),
+        );
+        let (ytmin, ytmax) = check_axis(
+            ray.origin.get_y(),
+            ray.direction.get_y(),
+            self.min.get_y(),
+            self.max.get_y(),
+        );
+        let (ztmin, ztmax) = check_axis(
+            ray.origin.get_z(),
+            ray.direction.get_z(),
+            self.min.get_z(),
+            self.max.get_z(),
+        );
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        tmin <= tmax
+    }
+
+    /// Splits the box in half along its longest axis
+    pub fn split(&self) -> (BoundingBox, BoundingBox) {
+        let dx = self.max.get_x() - self.min.get_x();
+        let dy = self.max.get_y() - self.min.get_y();
+        let dz = self.max.get_z() - self.min.get_z();
+        let greatest = dx.max(dy).max(dz);
+
+        let (mut x0, mut y0, mut z0) = (self.min.get_x(), self.min.get_y(), self.min.get_z());
+        let (mut x1, mut y1, mut z1) = (self.max.get_x(), self.max.get_y(), self.max.get_z());
+        if greatest == dx {
+            x0 += dx / 2.0;
+            x1 = x0;
+        } else if greatest == dy {
+            y0 += dy / 2.0;
+            y1 = y0;
+        } else {
+            z0 += dz / 2.0;
+            z1 = z0;
+        }
+
+        (
+            BoundingBox::new(self.min, point(x1, y1, z1)),
+            BoundingBox::new(point(x0, y0, z0), self.max),
+        )
+    }
+}
+
+impl Default for BoundingBox {
+    /// Empty box
+    fn default() -> Self {
+        BoundingBox {
+            min: point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformations::{rotation_x, rotation_y, scaling},
+        tuple::{normalize, vector},
+        utils::PI,
+    };
+
+    #[test]
+    fn creating_empty_bounding_box() {
+        let b = BoundingBox::default();
+        assert!(b.is_empty());
+        assert_eq!(f64::INFINITY, b.get_min().get_x());
+        assert_eq!(f64::NEG_INFINITY, b.get_max().get_x());
+    }
+
+    #[test]
+    fn adding_points_to_empty_bounding_box() {
+        let mut b = BoundingBox::default();
+        b.add_point(point(-5.0, 2.0, 0.0));
+        b.add_point(point(7.0, 0.0, -3.0));
+        assert!(!b.is_empty());
+        assert_eq!(point(-5.0, 0.0, -3.0), b.get_min());
+        assert_eq!(point(7.0, 2.0, 0.0), b.get_max());
+    }
+
+    #[test]
+    fn adding_one_bounding_box_to_another() {
+        let mut b1 = BoundingBox::new(point(-5.0, -2.0, 0.0), point(7.0, 4.0, 4.0));
+        let b2 = BoundingBox::new(point(8.0, -7.0, -2.0), point(14.0, 2.0, 8.0));
+        b1.add_box(&b2);
+        assert_eq!(point(-5.0, -7.0, -2.0), b1.get_min());
+        assert_eq!(point(14.0, 4.0, 8.0), b1.get_max());
+    }
+
+    #[test]
+    fn checking_if_box_contains_point() {
+        let b = BoundingBox::new(point(5.0, -2.0, 0.0), point(11.0, 4.0, 7.0));
+        let cases = [
+            (point(5.0, -2.0, 0.0), true),
+            (point(11.0, 4.0, 7.0), true),
+            (point(8.0, 1.0, 3.0), true),
+            (point(3.0, 0.0, 3.0), false),
+            (point(8.0, -4.0, 3.0), false),
+            (point(8.0, 1.0, -1.0), false),
+            (point(13.0, 1.0, 3.0), false),
+            (point(8.0, 5.0, 3.0), false),
+            (point(8.0, 1.0, 8.0), false),
+        ];
+        for (p, result) in cases.iter() {
+            assert_eq!(*result, b.contains_point(*p));
+        }
+    }
+
+    #[test]
+    fn checking_if_box_contains_box() {
+        let b = BoundingBox::new(point(5.0, -2.0, 0.0), point(11.0, 4.0, 7.0));
+        let cases = [
+            (point(5.0, -2.0, 0.0), point(11.0, 4.0, 7.0), true),
+            (point(6.0, -1.0, 1.0), point(10.0, 3.0, 6.0), true),
+            (point(4.0, -3.0, -1.0), point(10.0, 3.0, 6.0), false),
+            (point(6.0, -1.0, 1.0), point(12.0, 5.0, 8.0), false),
+        ];
+        for (min, max, result) in cases.iter() {
+            assert_eq!(*result, b.contains_box(&BoundingBox::new(*min, *max)));
+        }
+    }
+
+    #[test]
+    fn transforming_bounding_box() {
+        let b = BoundingBox::new(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0));
+        let b2 = b.transform(rotation_x(PI / 4.0) * rotation_y(PI / 4.0));
+        assert_eq!(
+            point(-std::f64::consts::SQRT_2, -1.70711, -1.70711),
+            b2.get_min()
+        );
+        assert_eq!(
+            point(std::f64::consts::SQRT_2, 1.70711, 1.70711),
+            b2.get_max()
+        );
+    }
+
+    #[test]
+    fn transforming_infinite_bounding_box() {
+        let b = BoundingBox::new(
+            point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            point(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+        let scaled = b.transform(scaling(2.0, 2.0, 2.0));
+        assert_eq!(f64::NEG_INFINITY, scaled.get_min().get_x());
+        assert_eq!(0.0, scaled.get_min().get_y());
+        assert_eq!(0.0, scaled.get_max().get_y());
+        let rotated = b.transform(rotation_y(PI / 4.0));
+        assert!(!rotated.is_finite());
+        assert!(!rotated.is_empty());
+    }
+
+    #[test]
+    fn intersecting_ray_with_bounding_box_at_origin() {
+        let b = BoundingBox::new(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0));
+        let cases = [
+            (point(5.0, 0.5, 0.0), vector(-1.0, 0.0, 0.0), true),
+            (point(-5.0, 0.5, 0.0), vector(1.0, 0.0, 0.0), true),
+            (point(0.5, 5.0, 0.0), vector(0.0, -1.0, 0.0), true),
+            (point(0.5, -5.0, 0.0), vector(0.0, 1.0, 0.0), true),
+            (point(0.5, 0.0, 5.0), vector(0.0, 0.0, -1.0), true),
+            (point(0.5, 0.0, -5.0), vector(0.0, 0.0, 1.0), true),
+            (point(0.0, 0.5, 0.0), vector(0.0, 0.0, 1.0), true),
+            (point(-2.0, 0.0, 0.0), vector(2.0, 4.0, 6.0), false),
+            (point(0.0, -2.0, 0.0), vector(6.0, 2.0, 4.0), false),
+            (point(0.0, 0.0, -2.0), vector(4.0, 6.0, 2.0), false),
+            (point(2.0, 0.0, 2.0), vector(0.0, 0.0, -1.0), false),
+            (point(0.0, 2.0, 2.0), vector(0.0, -1.0, 0.0), false),
+            (point(2.0, 2.0, 0.0), vector(-1.0, 0.0, 0.0), false),
+        ];
+        for (origin, direction, result) in cases.iter() {
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            assert_eq!(*result, b.intersects(&r));
+        }
+    }
+
+    #[test]
+    fn intersecting_ray_with_non_cubic_bounding_box() {
+        let b = BoundingBox::new(point(5.0, -2.0, 0.0), point(11.0, 4.0, 7.0));
+        let cases = [
+            (point(15.0, 1.0, 2.0), vector(-1.0, 0.0, 0.0), true),
+            (point(-5.0, -1.0, 4.0), vector(1.0, 0.0, 0.0), true),
+            (point(7.0, 6.0, 5.0), vector(0.0, -1.0, 0.0), true),
+            (point(9.0, -5.0, 6.0), vector(0.0, 1.0, 0.0), true),
+            (point(8.0, 2.0, 12.0), vector(0.0, 0.0, -1.0), true),
+            (point(6.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), true),
+            (point(8.0, 1.0, 3.5), vector(0.0, 0.0, 1.0), true),
+            (point(9.0, -1.0, -8.0), vector(2.0, 4.0, 6.0), false),
+            (point(8.0, 3.0, -4.0), vector(6.0, 2.0, 4.0), false),
+            (point(9.0, -1.0, -2.0), vector(4.0, 6.0, 2.0), false),
+            (point(4.0, 0.0, 9.0), vector(0.0, 0.0, -1.0), false),
+            (point(8.0, 6.0, -1.0), vector(0.0, -1.0, 0.0), false),
+            (point(12.0, 5.0, 4.0), vector(-1.0, 0.0, 0.0), false),
+        ];
+        for (origin, direction, result) in cases.iter() {
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            assert_eq!(*result, b.intersects(&r));
+        }
+    }
+
+    #[test]
+    fn intersecting_ray_with_infinite_bounding_box() {
+        let b = BoundingBox::infinite();
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        assert!(b.intersects(&r));
+    }
+
+    #[test]
+    fn splitting_bounding_box() {
+        let cases = [
+            // perfect cube is split along x axis
+            (
+                point(-1.0, -4.0, -5.0),
+                point(9.0, 6.0, 5.0),
+                point(4.0, 6.0, 5.0),
+                point(4.0, -4.0, -5.0),
+            ),
+            (
+                point(-1.0, -2.0, -3.0),
+                point(9.0, 5.5, 3.0),
+                point(4.0, 5.5, 3.0),
+                point(4.0, -2.0, -3.0),
+            ),
+            (
+                point(-1.0, -2.0, -3.0),
+                point(5.0, 8.0, 3.0),
+                point(5.0, 3.0, 3.0),
+                point(-1.0, 3.0, -3.0),
+            ),
+            (
+                point(-1.0, -2.0, -3.0),
+                point(5.0, 3.0, 7.0),
+                point(5.0, 3.0, 2.0),
+                point(-1.0, -2.0, 2.0),
+            ),
+        ];
+        for (min, max, left_max, right_min) in cases.iter() {
+            let b = BoundingBox::new(*min, *max);
+            let (left, right) = b.split();
+            assert_eq!(*min, left.get_min());
+            assert_eq!(*left_max, left.get_max());
+            assert_eq!(*right_min, right.get_min());
+            assert_eq!(*max, right.get_max());
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    canvas::Canvas,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    tuple::{normalize, point},
+    world::{World, MAX_REFLECTION_DEPTH},
+};
+
+/// Camera maps three-dimensional scene onto two-dimensional canvas. Canvas is always placed
+/// exactly one unit in front of the camera, so the field of view alone decides how much of the
+/// scene is visible
+#[derive(Debug, Copy, Clone)]
+pub struct Camera {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: f64,
+    transform: Matrix4,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+    /// Number of bounces between reflective surfaces followed by `render`
+    reflection_depth: usize,
+}
+
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Camera {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+        Camera {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix4::identity_matrix(),
+            half_width,
+            half_height,
+            pixel_size: (half_width * 2.0) / hsize as f64,
+            reflection_depth: MAX_REFLECTION_DEPTH,
+        }
+    }
+
+    pub fn get_hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn get_vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn get_field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    pub fn get_pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    pub fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    pub fn get_reflection_depth(&self) -> usize {
+        self.reflection_depth
+    }
+
+    pub fn set_reflection_depth(&mut self, depth: usize) {
+        self.reflection_depth = depth;
+    }
+
+    /// Returns ray which starts at the camera and passes through the center of given pixel
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Result<Ray, MatrixError> {
+        // offset from the edge of the canvas to the pixel's center
+        let xoffset = (px as f64 + 0.5) * self.pixel_size;
+        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+
+        // untransformed coordinates of the pixel in world space; camera looks toward -z, so +x
+        // is to the left
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        // canvas is at z = -1; transform canvas point and origin using inverse of camera's
+        // transformation matrix
+        let inverse = self.transform.inverse()?;
+        let pixel = inverse * point(world_x, world_y, -1.0);
+        let origin = inverse * point(0.0, 0.0, 0.0);
+        let direction = normalize(&(pixel - origin));
+
+        Ok(Ray { origin, direction })
+    }
+}
+
+/// Renders image of the world by casting a ray through every pixel of the camera's canvas
+pub fn render(camera: &Camera, world: &World) -> Result<Canvas, MatrixError> {
+    let mut image = Canvas::new(camera.hsize, camera.vsize);
+    for (y, row) in image.rows_mut().enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let ray = camera.ray_for_pixel(x, y)?;
+            *pixel = world.color_at(&ray, camera.reflection_depth)?;
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Color, BLACK},
+        light::PointLight,
+        material::Material,
+        plane::Plane,
+        shape::Shape,
+        transformations::{rotation_y, translation, view_transform},
+        tuple::vector,
+        utils::{eq_with_eps, PI},
+    };
+    use std::rc::Rc;
+
+    #[test]
+    fn constructing_camera() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(160, c.get_hsize());
+        assert_eq!(120, c.get_vsize());
+        assert!(eq_with_eps(PI / 2.0, c.get_field_of_view()));
+        assert_eq!(Matrix4::identity_matrix(), c.get_transform());
+    }
+
+    #[test]
+    fn pixel_size_for_horizontal_canvas() {
+        let c = Camera::new(200, 125, PI / 2.0);
+        assert!(eq_with_eps(0.01, c.get_pixel_size()));
+    }
+
+    #[test]
+    fn pixel_size_for_vertical_canvas() {
+        let c = Camera::new(125, 200, PI / 2.0);
+        assert!(eq_with_eps(0.01, c.get_pixel_size()));
+    }
+
+    #[test]
+    fn constructing_ray_through_center_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50).unwrap();
+        assert_eq!(point(0.0, 0.0, 0.0), r.origin);
+        assert_eq!(vector(0.0, 0.0, -1.0), r.direction);
+    }
+
+    #[test]
+    fn constructing_ray_through_corner_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0).unwrap();
+        assert_eq!(point(0.0, 0.0, 0.0), r.origin);
+        assert_eq!(vector(0.66519, 0.33259, -0.66851), r.direction);
+    }
+
+    #[test]
+    fn constructing_ray_when_camera_is_transformed() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(rotation_y(PI / 4.0) * translation(0.0, -2.0, 5.0));
+        let r = c.ray_for_pixel(100, 50).unwrap();
+        let two_sqrt = 2.0f64.sqrt();
+        assert_eq!(point(0.0, 2.0, -5.0), r.origin);
+        assert_eq!(vector(two_sqrt / 2.0, 0.0, -two_sqrt / 2.0), r.direction);
+    }
+
+    #[test]
+    fn rendering_world_with_camera() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = point(0.0, 0.0, -5.0);
+        let to = point(0.0, 0.0, 0.0);
+        let up = vector(0.0, 1.0, 0.0);
+        c.set_transform(view_transform(from, to, up));
+        let image = render(&c, &w).unwrap();
+        assert_eq!(
+            Color::new(0.38066, 0.47583, 0.2855),
+            image.pixel_at(5, 5).unwrap()
+        );
+    }
+
+    #[test]
+    fn camera_reflection_depth_defaults_to_maximum() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(MAX_REFLECTION_DEPTH, c.get_reflection_depth());
+    }
+
+    #[test]
+    fn rendering_between_parallel_mirrors_with_limited_depth() {
+        let mirror = Material {
+            reflective: 1.0,
+            ..Default::default()
+        };
+        let mut lower = Plane::default();
+        lower.set_transform(translation(0.0, -1.0, 0.0));
+        lower.set_material(mirror.clone());
+        let mut upper = Plane::default();
+        upper.set_transform(translation(0.0, 1.0, 0.0));
+        upper.set_material(mirror);
+        let w = World {
+            light: Some(PointLight {
+                position: point(0.0, 0.0, 0.0),
+                intensity: Color::new(1.0, 1.0, 1.0),
+            }),
+            objects: vec![Rc::new(lower), Rc::new(upper)],
+            bvh: None,
+        };
+        // single pixel looking diagonally at the upper mirror, so the ray bounces between both
+        let mut c = Camera::new(1, 1, PI / 2.0);
+        c.set_transform(view_transform(
+            point(0.0, 0.0, 0.0),
+            point(1.0, 1.0, 0.0),
+            vector(0.0, 0.0, 1.0),
+        ));
+        let ray = c.ray_for_pixel(0, 0).unwrap();
+        let mut previous = BLACK;
+        for depth in 0..4 {
+            c.set_reflection_depth(depth);
+            let color = render(&c, &w).unwrap().pixel_at(0, 0).unwrap();
+            assert_eq!(w.color_at(&ray, depth).unwrap(), color);
+            // every extra bounce adds light of the opposite mirror
+            assert!(color.get_red() > previous.get_red());
+            previous = color;
+        }
+    }
+}
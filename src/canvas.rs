@@ -1,13 +1,21 @@
 use crate::color::Color;
+use crate::tone_mapping::OutputTransform;
 use crate::utils::eq_with_eps;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+};
+use thiserror::Error;
 
 const MAX_LINE_LENGTH: usize = 70;
 
-#[derive(Debug)]
+/// Image made of pixels stored row after row in one contiguous buffer, so scanlines can be
+/// processed as slices. (0, 0) is the top left corner
+#[derive(Debug, Clone)]
 pub struct Canvas {
     width: usize,
     height: usize,
-    canvas: std::vec::Vec<std::vec::Vec<Color>>,
+    canvas: Vec<Color>,
 }
 
 #[derive(Debug)]
@@ -17,20 +25,14 @@ pub enum CanvasError {
 
 impl Canvas {
     pub fn new(w: usize, h: usize) -> Canvas {
-        let vec = vec![vec![Color::new(0.0, 0.0, 0.0); h]; w];
-        Canvas {
-            width: w,
-            height: h,
-            canvas: vec,
-        }
+        Self::new_with_color(w, h, Color::new(0.0, 0.0, 0.0))
     }
 
     pub fn new_with_color(w: usize, h: usize, c: Color) -> Canvas {
-        let vec = vec![vec![c; h]; w];
         Canvas {
             width: w,
             height: h,
-            canvas: vec,
+            canvas: vec![c; w * h],
         }
     }
 
@@ -42,24 +44,209 @@ impl Canvas {
         self.height
     }
 
-    pub fn pixel_at(&self, w: usize, h: usize) -> Result<Color, CanvasError> {
-        if w > self.width - 1 || h > self.height - 1 {
+    fn index(&self, w: usize, h: usize) -> Result<usize, CanvasError> {
+        if w >= self.width || h >= self.height {
             Err(CanvasError::OutOfCanvasBorder)
         } else {
-            Ok(self.canvas[w][h])
+            Ok(h * self.width + w)
         }
     }
 
+    pub fn pixel_at(&self, w: usize, h: usize) -> Result<Color, CanvasError> {
+        Ok(self.canvas[self.index(w, h)?])
+    }
+
     pub fn write_pixel(&mut self, w: usize, h: usize, c: Color) -> Result<(), CanvasError> {
-        if w > self.width - 1 || h > self.height - 1 {
-            Err(CanvasError::OutOfCanvasBorder)
+        let i = self.index(w, h)?;
+        self.canvas[i] = c;
+        Ok(())
+    }
+
+    /// All pixels, row after row
+    pub fn as_slice(&self) -> &[Color] {
+        &self.canvas
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Color] {
+        &mut self.canvas
+    }
+
+    pub fn row(&self, h: usize) -> Option<&[Color]> {
+        if h < self.height {
+            Some(&self.canvas[h * self.width..(h + 1) * self.width])
         } else {
-            self.canvas[w][h] = c;
-            Ok(())
+            None
+        }
+    }
+
+    pub fn row_mut(&mut self, h: usize) -> Option<&mut [Color]> {
+        if h < self.height {
+            Some(&mut self.canvas[h * self.width..(h + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Rows from the top of the image to the bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Color]> {
+        (0..self.height).map(move |h| &self.canvas[h * self.width..(h + 1) * self.width])
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Color]> {
+        // chunks can't be empty, so rows of zero width canvas come from `repeat_with`
+        let width = self.width;
+        let height = self.height;
+        self.canvas
+            .chunks_mut(width.max(1))
+            .chain(std::iter::repeat_with(|| &mut [][..]))
+            .take(height)
+    }
+
+    /// Every pixel with its (x, y) coordinates, in row-major order
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize, &Color)> {
+        let width = self.width.max(1);
+        self.canvas
+            .iter()
+            .enumerate()
+            .map(move |(i, c)| (i % width, i / width, c))
+    }
+
+    pub fn pixels_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Color)> {
+        let width = self.width.max(1);
+        self.canvas
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, c)| (i % width, i / width, c))
+    }
+
+    /// Mutable view of rectangular part of the canvas, with its own coordinates starting at the
+    /// top left corner of the region. The view can be split into disjoint regions
+    pub fn region_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<RegionMut<'_>, CanvasError> {
+        if x.checked_add(width).is_none_or(|r| r > self.width)
+            || y.checked_add(height).is_none_or(|b| b > self.height)
+        {
+            return Err(CanvasError::OutOfCanvasBorder);
+        }
+        let rows = self
+            .rows_mut()
+            .skip(y)
+            .take(height)
+            .map(|row| &mut row[x..x + width])
+            .collect();
+        Ok(RegionMut { rows, width })
+    }
+}
+
+/// Rectangular part of the canvas borrowed for writing. Splitting it gives regions which don't
+/// overlap, so every one of them can be handed to a different renderer, e.g. as a tile
+#[derive(Debug)]
+pub struct RegionMut<'a> {
+    /// Parts of canvas rows covered by the region, each `width` pixels long
+    rows: Vec<&'a mut [Color]>,
+    width: usize,
+}
+
+impl<'a> RegionMut<'a> {
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn pixel_at(&self, w: usize, h: usize) -> Result<Color, CanvasError> {
+        self.rows
+            .get(h)
+            .and_then(|row| row.get(w))
+            .copied()
+            .ok_or(CanvasError::OutOfCanvasBorder)
+    }
+
+    pub fn write_pixel(&mut self, w: usize, h: usize, c: Color) -> Result<(), CanvasError> {
+        let pixel = self
+            .rows
+            .get_mut(h)
+            .and_then(|row| row.get_mut(w))
+            .ok_or(CanvasError::OutOfCanvasBorder)?;
+        *pixel = c;
+        Ok(())
+    }
+
+    pub fn row_mut(&mut self, h: usize) -> Option<&mut [Color]> {
+        self.rows.get_mut(h).map(|row| &mut **row)
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Color]> + use<'_, 'a> {
+        self.rows.iter_mut().map(|row| &mut **row)
+    }
+
+    pub fn fill(&mut self, c: Color) {
+        for row in self.rows_mut() {
+            row.fill(c);
+        }
+    }
+
+    /// Splits the region into the part above row `h` and the part starting at it
+    pub fn split_at_row(self, h: usize) -> Result<(RegionMut<'a>, RegionMut<'a>), CanvasError> {
+        if h > self.rows.len() {
+            return Err(CanvasError::OutOfCanvasBorder);
+        }
+        let width = self.width;
+        let mut top = self.rows;
+        let bottom = top.split_off(h);
+        Ok((
+            RegionMut { rows: top, width },
+            RegionMut {
+                rows: bottom,
+                width,
+            },
+        ))
+    }
+
+    /// Splits the region into the part left of column `w` and the part starting at it
+    pub fn split_at_column(self, w: usize) -> Result<(RegionMut<'a>, RegionMut<'a>), CanvasError> {
+        if w > self.width {
+            return Err(CanvasError::OutOfCanvasBorder);
         }
+        let width = self.width;
+        let (left, right) = self.rows.into_iter().map(|row| row.split_at_mut(w)).unzip();
+        Ok((
+            RegionMut {
+                rows: left,
+                width: w,
+            },
+            RegionMut {
+                rows: right,
+                width: width - w,
+            },
+        ))
     }
 }
 
+/// Reasons why PPM data couldn't be turned into a canvas
+#[derive(Error, Debug)]
+pub enum PpmError {
+    #[error("Unsupported magic number `{0}`, expected P3 or P6")]
+    UnsupportedFormat(String),
+    #[error("Malformed PPM header: invalid or missing {0}")]
+    MalformedHeader(&'static str),
+    #[error("Malformed PPM body: sample {index} is not a number")]
+    MalformedSample { index: usize },
+    #[error("Sample {value} exceeds maximum color value {max}")]
+    SampleOutOfRange { value: u32, max: u32 },
+    #[error("Truncated PPM body: expected {expected} samples, found {found}")]
+    Truncated { expected: usize, found: usize },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
 #[derive(Default)]
 pub struct PPM {
     header: String,
@@ -90,57 +277,272 @@ fn color_to_scaled_integers(c: &Color, max: f64) -> Box<[u32]> {
     scaled
 }
 
-fn colors_to_scaled_vector(can: &Canvas, max: f64) -> String {
-    let mut scaled_colors: String = "".to_string();
-    for row in 0..can.get_height() {
-        let mut temp_scaled_colors: String = "".to_string();
-        for col in 0..can.get_width() {
-            let pixel = can.pixel_at(col, row).unwrap();
-            let scaled_pixel = color_to_scaled_integers(&pixel, max);
-            temp_scaled_colors += &scaled_pixel[0].to_string();
-            temp_scaled_colors += " ";
-            temp_scaled_colors += &scaled_pixel[1].to_string();
-            temp_scaled_colors += " ";
-            temp_scaled_colors += &scaled_pixel[2].to_string();
-            temp_scaled_colors += " ";
-        }
-        let len = temp_scaled_colors.len();
-        temp_scaled_colors.truncate(len - 1);
-        temp_scaled_colors += "\n";
-
-        scaled_colors += &temp_scaled_colors
-            .chars()
-            .enumerate()
-            .flat_map(|(i, c)| {
-                if i != 0
-                    && c == ' '
-                    && (i % MAX_LINE_LENGTH == 0
-                        || i % MAX_LINE_LENGTH == 67
-                        || i % MAX_LINE_LENGTH == 68
-                        || i % MAX_LINE_LENGTH == 69)
-                {
-                    Some('\n')
-                } else {
-                    None
-                }
-                .into_iter()
-                .chain(std::iter::once(c))
-            })
-            .collect::<String>()
-            .replace("\n ", "\n")[..];
+/// Writes one row of plain PPM, breaking lines so none of them is longer than MAX_LINE_LENGTH
+fn write_plain_row<W: Write>(
+    c: &Canvas,
+    row: usize,
+    max: f64,
+    transform: &OutputTransform,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut line = String::with_capacity(MAX_LINE_LENGTH + 1);
+    for pixel in c.row(row).expect("Row within canvas border") {
+        for sample in color_to_scaled_integers(&transform.apply(*pixel), max).iter() {
+            let sample = sample.to_string();
+            if !line.is_empty() && line.len() + 1 + sample.len() > MAX_LINE_LENGTH {
+                line.push('\n');
+                out.write_all(line.as_bytes())?;
+                line.clear();
+            } else if !line.is_empty() {
+                line.push(' ');
+            }
+            line += &sample;
+        }
     }
-    scaled_colors
+    line.push('\n');
+    out.write_all(line.as_bytes())
 }
 
+/// Writes one row of raw PPM - every sample takes one byte, or two (most significant first) for
+/// maximum color value above 255
+fn write_raw_row<W: Write>(
+    c: &Canvas,
+    row: usize,
+    max: f64,
+    transform: &OutputTransform,
+    out: &mut W,
+) -> io::Result<()> {
+    let wide = max > 255.0;
+    let mut bytes = Vec::with_capacity(c.width * if wide { 6 } else { 3 });
+    for pixel in c.row(row).expect("Row within canvas border") {
+        for sample in color_to_scaled_integers(&transform.apply(*pixel), max).iter() {
+            if wide {
+                bytes.extend_from_slice(&(*sample as u16).to_be_bytes());
+            } else {
+                bytes.push(*sample as u8);
+            }
+        }
+    }
+    out.write_all(&bytes)
+}
+
+/// Plain 8 bit PPM of the canvas held in memory, split into header and body. Thin wrapper over
+/// `write_ppm` - prefer streaming with `write_ppm` or `save_ppm`
 pub fn canvas_to_ppm(c: &Canvas) -> PPM {
-    let magic_number = "P3";
-    let maximum_color_value: u32 = 255;
-    let header: String = format!(
-        "{}\n{} {}\n{}",
-        magic_number, c.width, c.height, maximum_color_value
-    );
-    let body: String = colors_to_scaled_vector(c, maximum_color_value as f64);
-    PPM { header, body }
+    let mut out = Vec::new();
+    write_ppm(
+        c,
+        PpmFormat::Plain,
+        BitDepth::Eight,
+        &OutputTransform::default(),
+        &mut out,
+    )
+    .expect("Writing to memory can't fail");
+    let ppm = String::from_utf8(out).expect("Plain PPM consists of ASCII text only");
+    // Header is always magic number, size and maximum color value, each on its own line
+    let (header, body) = ppm
+        .match_indices('\n')
+        .nth(2)
+        .map_or((ppm.as_str(), ""), |(i, _)| (&ppm[..i], &ppm[i + 1..]));
+    PPM {
+        header: header.to_string(),
+        body: body.to_string(),
+    }
+}
+
+/// Reads whitespace separated tokens of PPM header and plain (P3) body, skipping comments
+struct PpmTokens<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PpmTokens<'a> {
+    fn next_token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.data.get(self.pos)? {
+                b'#' => {
+                    while !matches!(self.data.get(self.pos), None | Some(b'\n') | Some(b'\r')) {
+                        self.pos += 1;
+                    }
+                }
+                c if c.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+        let start = self.pos;
+        while matches!(self.data.get(self.pos), Some(c) if !c.is_ascii_whitespace() && *c != b'#') {
+            self.pos += 1;
+        }
+        Some(&self.data[start..self.pos])
+    }
+
+    fn next_number(&mut self) -> Option<u32> {
+        std::str::from_utf8(self.next_token()?).ok()?.parse().ok()
+    }
+
+    fn header_field(&mut self, name: &'static str) -> Result<u32, PpmError> {
+        match self.next_number() {
+            Some(n) if n > 0 => Ok(n),
+            _ => Err(PpmError::MalformedHeader(name)),
+        }
+    }
+}
+
+/// Parses plain (P3) or raw (P6) PPM image. Samples are scaled by the maximum color value, so
+/// colors of the canvas are in range [0, 1]; raw images with maximum above 255 use two bytes per
+/// sample, most significant first
+pub fn ppm_to_canvas(data: &[u8]) -> Result<Canvas, PpmError> {
+    let mut tokens = PpmTokens { data, pos: 0 };
+    let magic = tokens
+        .next_token()
+        .ok_or(PpmError::MalformedHeader("magic number"))?;
+    let raw = match magic {
+        b"P3" => false,
+        b"P6" => true,
+        _ => {
+            return Err(PpmError::UnsupportedFormat(
+                String::from_utf8_lossy(magic).into_owned(),
+            ))
+        }
+    };
+    let width = tokens.header_field("width")? as usize;
+    let height = tokens.header_field("height")? as usize;
+    let max = tokens.header_field("maximum color value")?;
+    if max > u16::MAX as u32 {
+        return Err(PpmError::MalformedHeader("maximum color value"));
+    }
+
+    // sizes come from untrusted header, so they must neither overflow nor be used to reserve memory
+    let expected = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(3))
+        .ok_or(PpmError::MalformedHeader("height"))?;
+    let mut samples = Vec::new();
+    if raw {
+        // exactly one whitespace character separates header from binary data, comment after
+        // maximum color value runs up to the newline ending it
+        let mut pos = tokens.pos;
+        if data.get(pos) == Some(&b'#') {
+            while !matches!(data.get(pos), None | Some(b'\n') | Some(b'\r')) {
+                pos += 1;
+            }
+        }
+        let body = data.get(pos + 1..).unwrap_or(&[]);
+        let bytes_per_sample = if max > 255 { 2 } else { 1 };
+        let found = body.len() / bytes_per_sample;
+        if found < expected {
+            return Err(PpmError::Truncated { expected, found });
+        }
+        samples.extend(body.chunks_exact(bytes_per_sample).take(expected).map(
+            |chunk| match chunk {
+                [hi, lo] => u32::from(*hi) << 8 | u32::from(*lo),
+                _ => u32::from(chunk[0]),
+            },
+        ));
+    } else {
+        while samples.len() < expected {
+            match tokens.next_token() {
+                Some(token) => samples.push(
+                    std::str::from_utf8(token)
+                        .ok()
+                        .and_then(|t| t.parse().ok())
+                        .ok_or(PpmError::MalformedSample {
+                            index: samples.len(),
+                        })?,
+                ),
+                None => break,
+            }
+        }
+    }
+    if samples.len() < expected {
+        return Err(PpmError::Truncated {
+            expected,
+            found: samples.len(),
+        });
+    }
+    if let Some(value) = samples.iter().copied().find(|s| *s > max) {
+        return Err(PpmError::SampleOutOfRange { value, max });
+    }
+
+    let mut canvas = Canvas::new(width, height);
+    let scale = max as f64;
+    for (pixel, rgb) in canvas.canvas.iter_mut().zip(samples.chunks_exact(3)) {
+        *pixel = Color::new(
+            rgb[0] as f64 / scale,
+            rgb[1] as f64 / scale,
+            rgb[2] as f64 / scale,
+        );
+    }
+    Ok(canvas)
+}
+
+pub fn load_ppm(path: &str) -> Result<Canvas, PpmError> {
+    ppm_to_canvas(&fs::read(path)?)
+}
+
+/// Encoding of PPM pixel data
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PpmFormat {
+    /// P3 - decimal numbers, readable but large
+    Plain,
+    /// P6 - binary samples
+    Raw,
+}
+
+/// Number of bits used for every color channel of exported image
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+impl BitDepth {
+    pub fn max_value(&self) -> u32 {
+        match self {
+            BitDepth::Eight => 255,
+            BitDepth::Sixteen => 65535,
+        }
+    }
+}
+
+/// Streams canvas as PPM image, row by row, so the whole image is never held in memory as text.
+/// Every pixel goes through the output transform before it is quantized
+pub fn write_ppm<W: Write>(
+    c: &Canvas,
+    format: PpmFormat,
+    depth: BitDepth,
+    transform: &OutputTransform,
+    out: &mut W,
+) -> io::Result<()> {
+    let magic_number = match format {
+        PpmFormat::Plain => "P3",
+        PpmFormat::Raw => "P6",
+    };
+    let max = depth.max_value();
+    write!(out, "{}\n{} {}\n{}\n", magic_number, c.width, c.height, max)?;
+    for row in 0..c.height {
+        match format {
+            PpmFormat::Plain => write_plain_row(c, row, max as f64, transform, out)?,
+            PpmFormat::Raw => write_raw_row(c, row, max as f64, transform, out)?,
+        }
+    }
+    out.flush()
+}
+
+pub fn save_ppm(
+    c: &Canvas,
+    path: &str,
+    format: PpmFormat,
+    depth: BitDepth,
+    transform: &OutputTransform,
+) -> io::Result<()> {
+    write_ppm(
+        c,
+        format,
+        depth,
+        transform,
+        &mut BufWriter::new(File::create(path)?),
+    )
 }
 
 #[cfg(test)]
@@ -165,6 +567,114 @@ mod tests {
         assert!(get_result.is_err());
     }
 
+    #[test]
+    fn pixels_are_stored_row_after_row() {
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(2, 0, Color::new(1.0, 0.0, 0.0)).unwrap();
+        c.write_pixel(0, 1, Color::new(0.0, 1.0, 0.0)).unwrap();
+        let slice = c.as_slice();
+        assert_eq!(6, slice.len());
+        assert_eq!(Color::new(1.0, 0.0, 0.0), slice[2]);
+        assert_eq!(Color::new(0.0, 1.0, 0.0), slice[3]);
+    }
+
+    #[test]
+    fn accessing_rows() {
+        let mut c = Canvas::new(3, 2);
+        c.row_mut(1).unwrap()[2] = Color::new(0.0, 0.0, 1.0);
+        assert_eq!(Color::new(0.0, 0.0, 1.0), c.pixel_at(2, 1).unwrap());
+        assert_eq!(3, c.row(0).unwrap().len());
+        assert!(c.row(2).is_none());
+        assert!(c.row_mut(2).is_none());
+        for (y, row) in c.rows_mut().enumerate() {
+            row[0] = Color::new(y as f64, 0.0, 0.0);
+        }
+        let firsts: Vec<Color> = c.rows().map(|row| row[0]).collect();
+        assert_eq!(
+            vec![Color::new(0.0, 0.0, 0.0), Color::new(1.0, 0.0, 0.0)],
+            firsts
+        );
+    }
+
+    #[test]
+    fn zero_width_canvas_has_empty_rows() {
+        let mut c = Canvas::new(0, 3);
+        assert_eq!(3, c.rows().count());
+        assert_eq!(3, c.rows_mut().count());
+        assert!(c.pixel_at(0, 0).is_err());
+    }
+
+    #[test]
+    fn iterating_over_pixels_with_coordinates() {
+        let mut c = Canvas::new(2, 2);
+        for (x, y, pixel) in c.pixels_mut() {
+            *pixel = Color::new(x as f64, y as f64, 0.0);
+        }
+        let coordinates: Vec<(usize, usize)> = c.pixels().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(vec![(0, 0), (1, 0), (0, 1), (1, 1)], coordinates);
+        assert_eq!(Color::new(1.0, 0.0, 0.0), c.pixel_at(1, 0).unwrap());
+        assert_eq!(Color::new(0.0, 1.0, 0.0), c.pixel_at(0, 1).unwrap());
+    }
+
+    #[test]
+    fn writing_through_region_view() {
+        let mut c = Canvas::new(4, 4);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        {
+            let mut region = c.region_mut(1, 2, 2, 2).unwrap();
+            assert_eq!(2, region.get_width());
+            assert_eq!(2, region.get_height());
+            region.fill(red);
+            region.write_pixel(1, 1, green).unwrap();
+            assert_eq!(green, region.pixel_at(1, 1).unwrap());
+            assert!(region.write_pixel(2, 0, green).is_err());
+            assert_eq!(2, region.row_mut(0).unwrap().len());
+            assert!(region.row_mut(2).is_none());
+        }
+        let painted: Vec<(usize, usize)> = c
+            .pixels()
+            .filter(|(_, _, p)| **p != Color::new(0.0, 0.0, 0.0))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert_eq!(vec![(1, 2), (2, 2), (1, 3), (2, 3)], painted);
+        assert_eq!(green, c.pixel_at(2, 3).unwrap());
+    }
+
+    #[test]
+    fn writing_through_split_regions_at_once() {
+        let mut c = Canvas::new(3, 2);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        {
+            let whole = c.region_mut(0, 0, 3, 2).unwrap();
+            let (mut left, right) = whole.split_at_column(1).unwrap();
+            let (mut top_right, mut bottom_right) = right.split_at_row(1).unwrap();
+            assert_eq!((1, 2), (left.get_width(), left.get_height()));
+            assert_eq!((2, 1), (top_right.get_width(), top_right.get_height()));
+            left.fill(red);
+            top_right.fill(green);
+            bottom_right.write_pixel(1, 0, blue).unwrap();
+            assert!(bottom_right.write_pixel(0, 1, blue).is_err());
+            assert!(bottom_right.split_at_row(2).is_err());
+        }
+        assert_eq!(red, c.pixel_at(0, 1).unwrap());
+        assert_eq!(green, c.pixel_at(2, 0).unwrap());
+        assert_eq!(Color::new(0.0, 0.0, 0.0), c.pixel_at(1, 1).unwrap());
+        assert_eq!(blue, c.pixel_at(2, 1).unwrap());
+    }
+
+    #[test]
+    fn region_outside_of_canvas_is_rejected() {
+        let mut c = Canvas::new(4, 4);
+        assert!(c.region_mut(3, 0, 2, 1).is_err());
+        assert!(c.region_mut(0, 2, 1, 3).is_err());
+        assert!(c.region_mut(1, 0, usize::MAX, 1).is_err());
+        assert!(c.region_mut(0, 1, 1, usize::MAX).is_err());
+        assert!(c.region_mut(0, 0, 4, 4).is_ok());
+    }
+
     #[test]
     fn constructing_ppm_header() {
         let c = Canvas::new(5, 3);
@@ -209,4 +719,278 @@ mod tests {
             ppm.body
         );
     }
+
+    #[test]
+    fn reading_file_with_wrong_magic_number() {
+        let ppm = "P32\n1 1\n255\n0 0 0";
+        assert!(matches!(
+            ppm_to_canvas(ppm.as_bytes()),
+            Err(PpmError::UnsupportedFormat(magic)) if magic == "P32"
+        ));
+    }
+
+    #[test]
+    fn reading_ppm_returns_canvas_of_right_size() {
+        let ppm = "P3\n10 2\n255\n".to_string() + &"0 0 0 ".repeat(20);
+        let c = ppm_to_canvas(ppm.as_bytes()).unwrap();
+        assert_eq!(10, c.get_width());
+        assert_eq!(2, c.get_height());
+    }
+
+    #[test]
+    fn reading_pixel_data_from_ppm_file() {
+        let ppm = "P3\n4 3\n255\n\
+                   255 127 0  0 127 255  127 255 0  255 255 255\n\
+                   0 0 0  255 0 0  0 255 0  0 0 255\n\
+                   255 255 0  0 255 255  255 0 255  127 127 127\n";
+        let c = ppm_to_canvas(ppm.as_bytes()).unwrap();
+        let cases = [
+            (0, 0, Color::new(1.0, 0.49804, 0.0)),
+            (1, 0, Color::new(0.0, 0.49804, 1.0)),
+            (2, 0, Color::new(0.49804, 1.0, 0.0)),
+            (3, 0, Color::new(1.0, 1.0, 1.0)),
+            (0, 1, Color::new(0.0, 0.0, 0.0)),
+            (1, 1, Color::new(1.0, 0.0, 0.0)),
+            (2, 1, Color::new(0.0, 1.0, 0.0)),
+            (3, 1, Color::new(0.0, 0.0, 1.0)),
+            (0, 2, Color::new(1.0, 1.0, 0.0)),
+            (1, 2, Color::new(0.0, 1.0, 1.0)),
+            (2, 2, Color::new(1.0, 0.0, 1.0)),
+            (3, 2, Color::new(0.49804, 0.49804, 0.49804)),
+        ];
+        for (x, y, color) in cases.iter() {
+            assert_eq!(*color, c.pixel_at(*x, *y).unwrap());
+        }
+    }
+
+    #[test]
+    fn ppm_parsing_ignores_comment_lines() {
+        let ppm = "P3\n# this is a comment\n2 1\n# this, too\n255\n\
+                   # another comment\n255 255 255\n# oh, no, comments in the pixel data!\n\
+                   255 0 255\n";
+        let c = ppm_to_canvas(ppm.as_bytes()).unwrap();
+        assert_eq!(Color::new(1.0, 1.0, 1.0), c.pixel_at(0, 0).unwrap());
+        assert_eq!(Color::new(1.0, 0.0, 1.0), c.pixel_at(1, 0).unwrap());
+    }
+
+    #[test]
+    fn raw_ppm_parsing_skips_comment_after_maximum_color_value() {
+        let mut ppm = b"P6\n1 1\n255#c\n".to_vec();
+        ppm.extend_from_slice(&[51, 153, 204]);
+        let c = ppm_to_canvas(&ppm).unwrap();
+        assert_eq!(Color::new(0.2, 0.6, 0.8), c.pixel_at(0, 0).unwrap());
+    }
+
+    #[test]
+    fn ppm_parsing_allows_rgb_triple_to_span_lines() {
+        let ppm = "P3\n1 1\n255\n51\n153\n\n204\n";
+        let c = ppm_to_canvas(ppm.as_bytes()).unwrap();
+        assert_eq!(Color::new(0.2, 0.6, 0.8), c.pixel_at(0, 0).unwrap());
+    }
+
+    #[test]
+    fn ppm_parsing_respects_scale_setting() {
+        let ppm = "P3 2 2 100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+        let c = ppm_to_canvas(ppm.as_bytes()).unwrap();
+        assert_eq!(Color::new(0.75, 0.5, 0.25), c.pixel_at(0, 1).unwrap());
+    }
+
+    #[test]
+    fn reading_binary_ppm() {
+        let mut ppm = b"P6\n# binary\n2 1\n255\n".to_vec();
+        // 10 is ASCII newline, which must be read as sample, not as whitespace
+        ppm.extend_from_slice(&[255, 0, 10, 0, 51, 255]);
+        let c = ppm_to_canvas(&ppm).unwrap();
+        assert_eq!(
+            Color::new(1.0, 0.0, 10.0 / 255.0),
+            c.pixel_at(0, 0).unwrap()
+        );
+        assert_eq!(Color::new(0.0, 0.2, 1.0), c.pixel_at(1, 0).unwrap());
+    }
+
+    #[test]
+    fn reading_binary_ppm_with_two_byte_samples() {
+        let mut ppm = b"P6 1 1 1000 ".to_vec();
+        ppm.extend_from_slice(&[0x03, 0xE8, 0x01, 0xF4, 0x00, 0x00]);
+        let c = ppm_to_canvas(&ppm).unwrap();
+        assert_eq!(Color::new(1.0, 0.5, 0.0), c.pixel_at(0, 0).unwrap());
+    }
+
+    #[test]
+    fn malformed_header_is_reported() {
+        let cases = [
+            ("", "magic number"),
+            ("P3\n", "width"),
+            ("P3\n-1 2\n255\n", "width"),
+            ("P3\n2 x\n255\n", "height"),
+            ("P3\n2 2\n", "maximum color value"),
+            ("P3\n2 2\n70000\n", "maximum color value"),
+        ];
+        for (ppm, field) in cases.iter() {
+            assert!(
+                matches!(ppm_to_canvas(ppm.as_bytes()), Err(PpmError::MalformedHeader(f)) if f == *field),
+                "{:?}",
+                ppm
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_data_is_reported() {
+        assert!(matches!(
+            ppm_to_canvas(b"P3\n2 1\n255\n0 0 0 255 255\n"),
+            Err(PpmError::Truncated {
+                expected: 6,
+                found: 5
+            })
+        ));
+        assert!(matches!(
+            ppm_to_canvas(b"P6\n2 1\n255\n\x00\x01\x02"),
+            Err(PpmError::Truncated {
+                expected: 6,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn huge_dimensions_are_reported_without_allocating() {
+        assert!(matches!(
+            ppm_to_canvas(b"P3 4000000000 4000000000 255\n0 0 0"),
+            Err(PpmError::MalformedHeader("height"))
+        ));
+        assert!(matches!(
+            ppm_to_canvas(b"P6 200000 200000 255\n\x00\x01\x02"),
+            Err(PpmError::Truncated {
+                expected: 120_000_000_000,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_samples_are_reported() {
+        assert!(matches!(
+            ppm_to_canvas(b"P3 1 1 255 0 abc 0"),
+            Err(PpmError::MalformedSample { index: 1 })
+        ));
+        assert!(matches!(
+            ppm_to_canvas(b"P3 1 1 15 0 16 0"),
+            Err(PpmError::SampleOutOfRange { value: 16, max: 15 })
+        ));
+    }
+
+    #[test]
+    fn written_ppm_can_be_read_back() {
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(1, 0, Color::new(1.0, 0.2, 0.4)).unwrap();
+        c.write_pixel(2, 1, Color::new(0.0, 0.6, 1.0)).unwrap();
+        let read = ppm_to_canvas(canvas_to_ppm(&c).get().as_bytes()).unwrap();
+        for x in 0..3 {
+            for y in 0..2 {
+                assert_eq!(c.pixel_at(x, y).unwrap(), read.pixel_at(x, y).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn writing_plain_ppm_matches_ppm_string() {
+        let c = Canvas::new_with_color(10, 2, Color::new(1.0, 0.8, 0.6));
+        let mut out = Vec::new();
+        write_ppm(
+            &c,
+            PpmFormat::Plain,
+            BitDepth::Eight,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(canvas_to_ppm(&c).get(), String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn writing_raw_ppm() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(0, 0, Color::new(1.5, 0.0, 0.2)).unwrap();
+        c.write_pixel(1, 0, Color::new(-0.5, 0.5, 1.0)).unwrap();
+        let mut out = Vec::new();
+        write_ppm(
+            &c,
+            PpmFormat::Raw,
+            BitDepth::Eight,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 0, 51, 0, 128, 255]);
+        assert_eq!(expected, out);
+    }
+
+    #[test]
+    fn writing_sixteen_bit_raw_ppm() {
+        let c = Canvas::new_with_color(1, 1, Color::new(1.0, 0.5, 0.0));
+        let mut out = Vec::new();
+        write_ppm(
+            &c,
+            PpmFormat::Raw,
+            BitDepth::Sixteen,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        let mut expected = b"P6\n1 1\n65535\n".to_vec();
+        expected.extend_from_slice(&[0xFF, 0xFF, 0x80, 0x00, 0x00, 0x00]);
+        assert_eq!(expected, out);
+    }
+
+    #[test]
+    fn sixteen_bit_plain_ppm_keeps_lines_short() {
+        let c = Canvas::new_with_color(10, 2, Color::new(1.0, 0.8, 0.6));
+        let mut out = Vec::new();
+        write_ppm(
+            &c,
+            PpmFormat::Plain,
+            BitDepth::Sixteen,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("P3\n10 2\n65535\n65535 52428 39321 "));
+        assert!(text.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+    }
+
+    #[test]
+    fn streamed_ppm_can_be_read_back() {
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(0, 1, Color::new(0.2, 0.4, 0.6)).unwrap();
+        c.write_pixel(2, 0, Color::new(1.0, 0.0, 0.8)).unwrap();
+        for format in [PpmFormat::Plain, PpmFormat::Raw].iter() {
+            for depth in [BitDepth::Eight, BitDepth::Sixteen].iter() {
+                let mut out = Vec::new();
+                write_ppm(&c, *format, *depth, &OutputTransform::default(), &mut out).unwrap();
+                let read = ppm_to_canvas(&out).unwrap();
+                for x in 0..3 {
+                    for y in 0..2 {
+                        assert_eq!(c.pixel_at(x, y).unwrap(), read.pixel_at(x, y).unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn output_transform_is_applied_on_export() {
+        let c = Canvas::new_with_color(1, 1, Color::new(0.2, 3.0, 0.0));
+        let transform = OutputTransform::new(
+            0.0,
+            crate::tone_mapping::ToneMapping::Reinhard,
+            crate::tone_mapping::TransferFunction::Srgb,
+        );
+        let mut out = Vec::new();
+        write_ppm(&c, PpmFormat::Raw, BitDepth::Eight, &transform, &mut out).unwrap();
+        // reinhard: 0.16667, 0.75, 0; srgb: 0.44505, 0.88082, 0
+        assert_eq!([113, 225, 0], out[out.len() - 3..]);
+    }
 }
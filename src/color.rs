@@ -7,6 +7,12 @@ pub const BLACK: Color = Color {
     blue: 0.0,
 };
 
+pub const WHITE: Color = Color {
+    red: 1.0,
+    green: 1.0,
+    blue: 1.0,
+};
+
 #[derive(Copy, Clone, Debug)]
 pub struct Color {
     red: f64,
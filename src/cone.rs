@@ -0,0 +1,361 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{point, vector, Tuple, TupleT},
+    utils::{eq_with_eps, EPSILON},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+// Cone is double-napped (x^2 + z^2 = y^2) with apex at (0, 0, 0) in object space, so its radius
+// at any height equals |y|. Like cylinder it's infinite by default and can be truncated on y axis
+#[derive(Debug, Clone)]
+pub struct Cone {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+}
+
+impl Cone {
+    /// Creates cone truncated at given bounds; closed cone has caps on both ends
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Cone {
+        Cone {
+            minimum,
+            maximum,
+            closed,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    pub fn get_maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Checks if intersection at t is within the radius of the cap; radius of the cap is the
+    /// same as the y coordinate of the plane it lies on
+    fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+        let x = ray.origin.get_x() + t * ray.direction.get_x();
+        let z = ray.origin.get_z() + t * ray.direction.get_z();
+        x.powi(2) + z.powi(2) <= radius.powi(2)
+    }
+
+    fn intersect_caps(self: &Rc<Self>, ray: &Ray, xs: &mut Intersections) {
+        // caps matter only if cone is closed and ray can actually reach them
+        if !self.closed || ray.direction.get_y().abs() < EPSILON {
+            return;
+        }
+
+        for bound in &[self.minimum, self.maximum] {
+            let t = (bound - ray.origin.get_y()) / ray.direction.get_y();
+            if Self::check_cap(ray, t, bound.abs()) {
+                xs.add(Intersection::new(t, self.clone()));
+            }
+        }
+    }
+
+    fn add_if_within_bounds(self: &Rc<Self>, ray: &Ray, t: f64, xs: &mut Intersections) {
+        let y = ray.origin.get_y() + t * ray.direction.get_y();
+        if self.minimum < y && y < self.maximum {
+            xs.add(Intersection::new(t, self.clone()));
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Cone
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        let (ox, oy, oz) = (ray.origin.get_x(), ray.origin.get_y(), ray.origin.get_z());
+        let (dx, dy, dz) = (
+            ray.direction.get_x(),
+            ray.direction.get_y(),
+            ray.direction.get_z(),
+        );
+
+        let a = dx.powi(2) - dy.powi(2) + dz.powi(2);
+        let b = 2.0 * ox * dx - 2.0 * oy * dy + 2.0 * oz * dz;
+        let c = ox.powi(2) - oy.powi(2) + oz.powi(2);
+
+        if eq_with_eps(a, 0.0) {
+            // ray is parallel to one of the cone's halves - it crosses the other half exactly
+            // once, unless it goes through the apex as well
+            if !eq_with_eps(b, 0.0) {
+                self.add_if_within_bounds(ray, -c / (2.0 * b), &mut xs);
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            // tangent rays might end up with slightly negative discriminant due to floating point
+            // inaccuracy
+            if discriminant > -EPSILON {
+                let sqrt_discriminant = discriminant.max(0.0).sqrt();
+                let mut t0 = (-b - sqrt_discriminant) / (2.0 * a);
+                let mut t1 = (-b + sqrt_discriminant) / (2.0 * a);
+                if t0 > t1 {
+                    std::mem::swap(&mut t0, &mut t1);
+                }
+                self.add_if_within_bounds(ray, t0, &mut xs);
+                self.add_if_within_bounds(ray, t1, &mut xs);
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Ok(xs)
+    }
+
+    /// Normal on the walls is tilted away from y axis by the same angle as the walls are; caps
+    /// have normals parallel to y axis
+    fn local_normal_at(&self, object_point: Tuple) -> Tuple {
+        let (x, y, z) = (
+            object_point.get_x(),
+            object_point.get_y(),
+            object_point.get_z(),
+        );
+        let dist = x.powi(2) + z.powi(2);
+        if dist < self.maximum.powi(2) && y >= self.maximum - EPSILON {
+            vector(0.0, 1.0, 0.0)
+        } else if dist < self.minimum.powi(2) && y <= self.minimum + EPSILON {
+            vector(0.0, -1.0, 0.0)
+        } else if eq_with_eps(dist, 0.0) {
+            // apex is a singular point - there is no tangent plane there, so normal along y axis
+            // is used instead of a zero vector, which couldn't be normalized
+            vector(0.0, if y > 0.0 { 1.0 } else { -1.0 }, 0.0)
+        } else {
+            let normal_y = dist.sqrt();
+            vector(x, if y > 0.0 { -normal_y } else { normal_y }, z)
+        }
+    }
+
+    /// Radius of the cone equals |y|, so the widest part is at whichever end is further from
+    /// the apex
+    fn bounds(&self) -> BoundingBox {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            point(-limit, self.minimum, -limit),
+            point(limit, self.maximum, limit),
+        )
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Cone {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Cone {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{intersections::intersect, transformations::translation, tuple::normalize};
+
+    #[test]
+    fn cone_has_cone_shape_type() {
+        let c = Cone::default();
+        assert_eq!(ShapeType::Cone, c.get_shape_type());
+    }
+
+    #[test]
+    fn intersecting_cone_with_ray() {
+        let cases = [
+            (point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), 5.0, 5.0),
+            (
+                point(0.0, 0.0, -5.0),
+                vector(1.0, 1.0, 1.0),
+                8.66025,
+                8.66025,
+            ),
+            (
+                point(1.0, 1.0, -5.0),
+                vector(-0.5, -1.0, 1.0),
+                4.55006,
+                49.44994,
+            ),
+        ];
+        for (origin, direction, t0, t1) in cases.iter() {
+            let c = Rc::new(Cone::default());
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(2, xs.len());
+            assert!(eq_with_eps(*t0, xs[0].t));
+            assert!(eq_with_eps(*t1, xs[1].t));
+        }
+    }
+
+    #[test]
+    fn intersecting_cone_with_ray_parallel_to_one_of_its_halves() {
+        let c = Rc::new(Cone::default());
+        let r = Ray {
+            origin: point(0.0, 0.0, -1.0),
+            direction: normalize(&vector(0.0, 1.0, 1.0)),
+        };
+        let xs = c.local_intersect(&r).unwrap();
+        assert_eq!(1, xs.len());
+        assert!(eq_with_eps(0.35355, xs[0].t));
+    }
+
+    #[test]
+    fn ray_parallel_to_half_through_apex_misses_cone() {
+        let c = Rc::new(Cone::default());
+        let r = Ray {
+            origin: point(0.0, -1.0, -1.0),
+            direction: normalize(&vector(0.0, 1.0, 1.0)),
+        };
+        let xs = c.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersecting_cones_end_caps() {
+        let cases = [
+            (point(0.0, 0.0, -5.0), vector(0.0, 1.0, 0.0), 0),
+            (point(0.0, 0.0, -0.25), vector(0.0, 1.0, 1.0), 2),
+            (point(0.0, 0.0, -0.25), vector(0.0, 1.0, 0.0), 4),
+        ];
+        for (origin, direction, count) in cases.iter() {
+            let c = Rc::new(Cone::new(-0.5, 0.5, true));
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(*count, xs.len());
+        }
+    }
+
+    #[test]
+    fn computing_normal_vector_on_cone() {
+        let two_sqrt = 2.0f64.sqrt();
+        let cases = [
+            (point(1.0, 1.0, 1.0), vector(1.0, -two_sqrt, 1.0)),
+            (point(-1.0, -1.0, 0.0), vector(-1.0, 1.0, 0.0)),
+        ];
+        for (p, expected) in cases.iter() {
+            let c = Cone::default();
+            assert_eq!(*expected, c.local_normal_at(*p));
+        }
+    }
+
+    #[test]
+    fn normal_at_apex_of_cone_is_not_degenerated() {
+        let c = Cone::default();
+        assert_eq!(
+            vector(0.0, -1.0, 0.0),
+            c.local_normal_at(point(0.0, 0.0, 0.0))
+        );
+        let n = c.normal_at(point(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(vector(0.0, -1.0, 0.0), n);
+    }
+
+    #[test]
+    fn normal_vector_on_cones_end_caps() {
+        let cases = [
+            (point(0.0, -1.0, 0.0), vector(0.0, -1.0, 0.0)),
+            (point(0.5, -1.0, 0.0), vector(0.0, -1.0, 0.0)),
+            (point(0.0, 2.0, 0.0), vector(0.0, 1.0, 0.0)),
+            (point(0.5, 2.0, 0.5), vector(0.0, 1.0, 0.0)),
+        ];
+        for (p, expected) in cases.iter() {
+            let c = Cone::new(-1.0, 2.0, true);
+            assert_eq!(*expected, c.local_normal_at(*p));
+        }
+    }
+
+    #[test]
+    fn intersecting_transformed_cone() {
+        let mut c = Cone::default();
+        c.set_transform(translation(0.0, 0.0, 1.0));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = intersect(Rc::new(c), &r).unwrap();
+        assert_eq!(2, xs.len());
+        assert!(eq_with_eps(6.0, xs[0].t));
+    }
+
+    #[test]
+    fn bounded_cone_has_bounding_box() {
+        let c = Cone::new(-5.0, 3.0, false);
+        let b = c.bounds();
+        assert_eq!(point(-5.0, -5.0, -5.0), b.get_min());
+        assert_eq!(point(5.0, 3.0, 5.0), b.get_max());
+    }
+
+    #[test]
+    fn unbounded_cone_has_infinite_bounding_box() {
+        let b = Cone::default().bounds();
+        assert!(!b.is_finite());
+        assert_eq!(f64::NEG_INFINITY, b.get_min().get_x());
+        assert_eq!(f64::INFINITY, b.get_max().get_y());
+    }
+}
@@ -0,0 +1,356 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{intersect, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{vector, Tuple},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CsgOperation {
+    /// Everything from both shapes, except their insides
+    Union,
+    /// Only the part shared by both shapes
+    Intersection,
+    /// Left shape with the right shape carved out of it
+    Difference,
+}
+
+/// Constructive solid geometry - combination of two shapes using a set operation
+#[derive(Debug)]
+pub struct Csg {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    operation: CsgOperation,
+    left: Rc<dyn Shape>,
+    right: Rc<dyn Shape>,
+    bounds: BoundingBox,
+}
+
+/// Decides if intersection is a part of the composite surface. `lhit` tells if left shape was
+/// hit, `inl` and `inr` tell if the hit occurred inside the left and right shape respectively
+pub fn intersection_allowed(op: CsgOperation, lhit: bool, inl: bool, inr: bool) -> bool {
+    match op {
+        CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+        CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+        CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+    }
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Rc<dyn Shape>, right: Rc<dyn Shape>) -> Csg {
+        let mut bounds = left.parent_space_bounds();
+        bounds.add_box(&right.parent_space_bounds());
+        Csg {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            operation,
+            left,
+            right,
+            bounds,
+        }
+    }
+
+    pub fn get_operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn get_left(&self) -> &Rc<dyn Shape> {
+        &self.left
+    }
+
+    pub fn get_right(&self) -> &Rc<dyn Shape> {
+        &self.right
+    }
+
+    /// Walks sorted intersections of both children, tracking whether the ray is currently inside
+    /// left or right shape, and keeps only those which lie on the surface of composite solid
+    pub fn filter_intersections(&self, xs: &Intersections) -> Intersections {
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = Intersections::new();
+        for i in xs.iter() {
+            let lhit = self.left.includes(&**i.object.borrow());
+            if intersection_allowed(self.operation, lhit, inl, inr) {
+                result.add(i.clone());
+            }
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+        result
+    }
+}
+
+impl Shape for Csg {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Csg
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    /// Like group, CSG reports intersections of its children, with its own transformation
+    /// recorded in them
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        if !self.bounds.intersects(ray) {
+            return Ok(Intersections::new());
+        }
+        let mut xs = intersect(self.left.clone(), ray)?;
+        let mut right_xs = intersect(self.right.clone(), ray)?;
+        xs.append(&mut right_xs);
+        xs.sort();
+        let mut result = self.filter_intersections(&xs);
+        for i in result.iter_mut() {
+            i.parent_transform = self.transform * i.parent_transform;
+        }
+        Ok(result)
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+
+    /// CSG has no surface of its own - it is never hit directly, normals are computed for its
+    /// children - so its normal is zero vector
+    fn local_normal_at(&self, _object_point: Tuple) -> Tuple {
+        vector(0.0, 0.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Children are divided only if they aren't shared with other shapes
+    fn divide(&mut self, threshold: usize) {
+        if let Some(left) = Rc::get_mut(&mut self.left) {
+            left.divide(threshold);
+        }
+        if let Some(right) = Rc::get_mut(&mut self.right) {
+            right.divide(threshold);
+        }
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Csg {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::Cube, group::Group, intersections::Intersection, sphere::Sphere,
+        transformations::translation, tuple::point, utils::eq_with_eps,
+    };
+
+    #[test]
+    fn csg_is_created_with_operation_and_two_shapes() {
+        let s1: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let s2: Rc<dyn Shape> = Rc::new(Cube::default());
+        let c = Csg::new(CsgOperation::Union, s1.clone(), s2.clone());
+        assert_eq!(ShapeType::Csg, c.get_shape_type());
+        assert_eq!(CsgOperation::Union, c.get_operation());
+        assert_eq!(s1.get_id(), c.get_left().get_id());
+        assert_eq!(s2.get_id(), c.get_right().get_id());
+        assert!(c.includes(&*s1));
+        assert!(c.includes(&*s2));
+    }
+
+    #[test]
+    fn csg_has_no_normal_of_its_own() {
+        let s1: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let s2: Rc<dyn Shape> = Rc::new(Cube::default());
+        let c = Csg::new(CsgOperation::Union, s1, s2);
+        assert_eq!(
+            vector(0.0, 0.0, 0.0),
+            c.normal_at(point(1.0, 0.0, 0.0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn evaluating_rule_for_csg_operation() {
+        use CsgOperation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for (op, lhit, inl, inr, result) in cases.iter() {
+            assert_eq!(*result, intersection_allowed(*op, *lhit, *inl, *inr));
+        }
+    }
+
+    #[test]
+    fn filtering_list_of_intersections() {
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+        for (op, x0, x1) in cases.iter() {
+            let s1: Rc<dyn Shape> = Rc::new(Sphere::default());
+            let s2: Rc<dyn Shape> = Rc::new(Cube::default());
+            let c = Csg::new(*op, s1.clone(), s2.clone());
+            let mut xs = Intersections::new();
+            xs.add(Intersection::new(1.0, s1.clone()));
+            xs.add(Intersection::new(2.0, s2.clone()));
+            xs.add(Intersection::new(3.0, s1.clone()));
+            xs.add(Intersection::new(4.0, s2.clone()));
+            let result = c.filter_intersections(&xs);
+            assert_eq!(2, result.len());
+            assert_eq!(xs[*x0], result[0]);
+            assert_eq!(xs[*x1], result[1]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg_object() {
+        let c = Rc::new(Csg::new(
+            CsgOperation::Union,
+            Rc::new(Sphere::default()),
+            Rc::new(Cube::default()),
+        ));
+        let r = Ray {
+            origin: point(0.0, 2.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = c.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg_object() {
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Rc::new(Csg::new(
+            CsgOperation::Union,
+            Rc::new(s1.clone()),
+            Rc::new(s2.clone()),
+        ));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = c.local_intersect(&r).unwrap();
+        assert_eq!(2, xs.len());
+        assert!(eq_with_eps(4.0, xs[0].t));
+        assert_eq!(s1.get_id(), xs[0].object.borrow().get_id());
+        assert!(eq_with_eps(6.5, xs[1].t));
+        assert_eq!(s2.get_id(), xs[1].object.borrow().get_id());
+    }
+
+    #[test]
+    fn csg_of_groups_checks_descendants() {
+        let s1: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let mut g = Group::default();
+        g.add_child(s1.clone());
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, 0.5));
+        let c = Rc::new(Csg::new(
+            CsgOperation::Difference,
+            Rc::new(g),
+            Rc::new(s2.clone()),
+        ));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = c.local_intersect(&r).unwrap();
+        assert_eq!(2, xs.len());
+        assert!(eq_with_eps(4.0, xs[0].t));
+        assert!(eq_with_eps(4.5, xs[1].t));
+        assert_eq!(s2.get_id(), xs[1].object.borrow().get_id());
+    }
+
+    #[test]
+    fn csg_has_bounding_box_containing_its_children() {
+        let left: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let mut right = Sphere::default();
+        right.set_transform(translation(2.0, 3.0, 4.0));
+        let c = Csg::new(CsgOperation::Difference, left, Rc::new(right));
+        let b = c.bounds();
+        assert_eq!(point(-1.0, -1.0, -1.0), b.get_min());
+        assert_eq!(point(3.0, 4.0, 5.0), b.get_max());
+    }
+
+    #[test]
+    fn intersecting_ray_and_csg_does_not_test_children_if_box_is_missed() {
+        let c = Rc::new(Csg::new(
+            CsgOperation::Union,
+            Rc::new(Sphere::default()),
+            Rc::new(Cube::default()),
+        ));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        assert!(c.local_intersect(&r).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    bounds::{check_axis, BoundingBox},
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{point, vector, Tuple, TupleT},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+// Cube is axis-aligned and spans from -1 to 1 on every axis in object space
+#[derive(Debug, Clone)]
+pub struct Cube {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+}
+
+impl Shape for Cube {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Cube
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let (xtmin, xtmax) = check_axis(ray.origin.get_x(), ray.direction.get_x(), -1.0, 1.0);
+        let (ytmin, ytmax) = check_axis(ray.origin.get_y(), ray.direction.get_y(), -1.0, 1.0);
+        let (ztmin, ztmax) = check_axis(ray.origin.get_z(), ray.direction.get_z(), -1.0, 1.0);
+
+        // ray is inside the cube only between the last slab it enters and the first slab it
+        // leaves
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        let mut xs = Intersections::new();
+        if tmin <= tmax {
+            xs.add(Intersection::new(tmin, self.clone()));
+            xs.add(Intersection::new(tmax, self));
+        }
+        Ok(xs)
+    }
+
+    /// Normal points along the axis with the largest absolute component - the face the point
+    /// lies on. On edges and corners one of the adjacent faces is picked
+    fn local_normal_at(&self, object_point: Tuple) -> Tuple {
+        let (x, y, z) = (
+            object_point.get_x(),
+            object_point.get_y(),
+            object_point.get_z(),
+        );
+        let maxc = x.abs().max(y.abs()).max(z.abs());
+        if maxc == x.abs() {
+            vector(x, 0.0, 0.0)
+        } else if maxc == y.abs() {
+            vector(0.0, y, 0.0)
+        } else {
+            vector(0.0, 0.0, z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0))
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Cube {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Cube {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        intersections::intersect,
+        transformations::{rotation_y, scaling, translation},
+        tuple::normalize,
+        utils::{eq_with_eps, PI},
+    };
+
+    #[test]
+    fn cube_has_cube_shape_type() {
+        let c = Cube::default();
+        assert_eq!(ShapeType::Cube, c.get_shape_type());
+    }
+
+    #[test]
+    fn ray_intersects_cube() {
+        let cases = [
+            // +x, -x, +y, -y, +z, -z, inside
+            (point(5.0, 0.5, 0.0), vector(-1.0, 0.0, 0.0), 4.0, 6.0),
+            (point(-5.0, 0.5, 0.0), vector(1.0, 0.0, 0.0), 4.0, 6.0),
+            (point(0.5, 5.0, 0.0), vector(0.0, -1.0, 0.0), 4.0, 6.0),
+            (point(0.5, -5.0, 0.0), vector(0.0, 1.0, 0.0), 4.0, 6.0),
+            (point(0.5, 0.0, 5.0), vector(0.0, 0.0, -1.0), 4.0, 6.0),
+            (point(0.5, 0.0, -5.0), vector(0.0, 0.0, 1.0), 4.0, 6.0),
+            (point(0.0, 0.5, 0.0), vector(0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+        for (origin, direction, t1, t2) in cases.iter() {
+            let c = Rc::new(Cube::default());
+            let r = Ray {
+                origin: *origin,
+                direction: *direction,
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(2, xs.len());
+            assert!(eq_with_eps(*t1, xs[0].t));
+            assert!(eq_with_eps(*t2, xs[1].t));
+        }
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let cases = [
+            (point(-2.0, 0.0, 0.0), vector(0.2673, 0.5345, 0.8018)),
+            (point(0.0, -2.0, 0.0), vector(0.8018, 0.2673, 0.5345)),
+            (point(0.0, 0.0, -2.0), vector(0.5345, 0.8018, 0.2673)),
+            (point(2.0, 0.0, 2.0), vector(0.0, 0.0, -1.0)),
+            (point(0.0, 2.0, 2.0), vector(0.0, -1.0, 0.0)),
+            (point(2.0, 2.0, 0.0), vector(-1.0, 0.0, 0.0)),
+        ];
+        for (origin, direction) in cases.iter() {
+            let c = Rc::new(Cube::default());
+            let r = Ray {
+                origin: *origin,
+                direction: *direction,
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert!(xs.is_empty());
+        }
+    }
+
+    #[test]
+    fn normal_on_surface_of_cube() {
+        let cases = [
+            (point(1.0, 0.5, -0.8), vector(1.0, 0.0, 0.0)),
+            (point(-1.0, -0.2, 0.9), vector(-1.0, 0.0, 0.0)),
+            (point(-0.4, 1.0, -0.1), vector(0.0, 1.0, 0.0)),
+            (point(0.3, -1.0, -0.7), vector(0.0, -1.0, 0.0)),
+            (point(-0.6, 0.3, 1.0), vector(0.0, 0.0, 1.0)),
+            (point(0.4, 0.4, -1.0), vector(0.0, 0.0, -1.0)),
+            // corners
+            (point(1.0, 1.0, 1.0), vector(1.0, 0.0, 0.0)),
+            (point(-1.0, -1.0, -1.0), vector(-1.0, 0.0, 0.0)),
+        ];
+        for (p, expected) in cases.iter() {
+            let c = Cube::default();
+            assert_eq!(*expected, c.local_normal_at(*p));
+        }
+    }
+
+    #[test]
+    fn normal_on_edge_of_cube() {
+        let c = Cube::default();
+        assert_eq!(
+            vector(0.0, 1.0, 0.0),
+            c.local_normal_at(point(0.5, 1.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn intersecting_transformed_cube() {
+        let mut c = Cube::default();
+        c.set_transform(translation(0.0, 0.0, 3.0) * scaling(2.0, 2.0, 2.0));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = intersect(Rc::new(c), &r).unwrap();
+        assert_eq!(2, xs.len());
+        assert!(eq_with_eps(6.0, xs[0].t));
+        assert!(eq_with_eps(10.0, xs[1].t));
+    }
+
+    #[test]
+    fn normal_on_rotated_cube() {
+        let mut c = Cube::default();
+        c.set_transform(rotation_y(PI / 4.0));
+        let two_sqrt = 2.0f64.sqrt();
+        let n = c
+            .normal_at(point(-two_sqrt / 2.0, 0.0, -two_sqrt / 2.0))
+            .unwrap();
+        assert_eq!(normalize(&vector(-1.0, 0.0, -1.0)), n);
+    }
+
+    #[test]
+    fn cube_has_bounding_box() {
+        let c = Cube::default();
+        let b = c.bounds();
+        assert_eq!(point(-1.0, -1.0, -1.0), b.get_min());
+        assert_eq!(point(1.0, 1.0, 1.0), b.get_max());
+    }
+}
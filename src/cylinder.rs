@@ -0,0 +1,350 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{point, vector, Tuple, TupleT},
+    utils::{eq_with_eps, EPSILON},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+// Cylinder has radius 1 and is centered around y axis in object space. By default it's infinitely
+// long; minimum and maximum truncate it on y axis (both bounds are exclusive)
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+}
+
+impl Cylinder {
+    /// Creates cylinder truncated at given bounds; closed cylinder has caps on both ends
+    pub fn new(minimum: f64, maximum: f64, closed: bool) -> Cylinder {
+        Cylinder {
+            minimum,
+            maximum,
+            closed,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    pub fn get_maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Checks if intersection at t is within the radius of the cap
+    fn check_cap(ray: &Ray, t: f64) -> bool {
+        let x = ray.origin.get_x() + t * ray.direction.get_x();
+        let z = ray.origin.get_z() + t * ray.direction.get_z();
+        x.powi(2) + z.powi(2) <= 1.0
+    }
+
+    fn intersect_caps(self: &Rc<Self>, ray: &Ray, xs: &mut Intersections) {
+        // caps matter only if cylinder is closed and ray can actually reach them
+        if !self.closed || ray.direction.get_y().abs() < EPSILON {
+            return;
+        }
+
+        for bound in &[self.minimum, self.maximum] {
+            let t = (bound - ray.origin.get_y()) / ray.direction.get_y();
+            if Self::check_cap(ray, t) {
+                xs.add(Intersection::new(t, self.clone()));
+            }
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Cylinder
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        let (ox, oy, oz) = (ray.origin.get_x(), ray.origin.get_y(), ray.origin.get_z());
+        let (dx, dy, dz) = (
+            ray.direction.get_x(),
+            ray.direction.get_y(),
+            ray.direction.get_z(),
+        );
+
+        let a = dx.powi(2) + dz.powi(2);
+        // ray parallel to y axis can't hit the walls, only the caps
+        if !eq_with_eps(a, 0.0) {
+            let b = 2.0 * ox * dx + 2.0 * oz * dz;
+            let c = ox.powi(2) + oz.powi(2) - 1.0;
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Ok(xs);
+            }
+
+            let sqrt_discriminant = discriminant.sqrt();
+            let mut t0 = (-b - sqrt_discriminant) / (2.0 * a);
+            let mut t1 = (-b + sqrt_discriminant) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            for t in &[t0, t1] {
+                let y = oy + t * dy;
+                if self.minimum < y && y < self.maximum {
+                    xs.add(Intersection::new(*t, self.clone()));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Ok(xs)
+    }
+
+    /// Normal on the walls points away from y axis; caps have normals parallel to y axis
+    fn local_normal_at(&self, object_point: Tuple) -> Tuple {
+        let (x, y, z) = (
+            object_point.get_x(),
+            object_point.get_y(),
+            object_point.get_z(),
+        );
+        let dist = x.powi(2) + z.powi(2);
+        if dist < 1.0 && y >= self.maximum - EPSILON {
+            vector(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && y <= self.minimum + EPSILON {
+            vector(0.0, -1.0, 0.0)
+        } else {
+            vector(x, 0.0, z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            point(-1.0, self.minimum, -1.0),
+            point(1.0, self.maximum, 1.0),
+        )
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Cylinder {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Cylinder {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::normalize;
+
+    #[test]
+    fn cylinder_has_cylinder_shape_type() {
+        let c = Cylinder::default();
+        assert_eq!(ShapeType::Cylinder, c.get_shape_type());
+    }
+
+    #[test]
+    fn ray_misses_cylinder() {
+        let cases = [
+            (point(1.0, 0.0, 0.0), vector(0.0, 1.0, 0.0)),
+            (point(0.0, 0.0, 0.0), vector(0.0, 1.0, 0.0)),
+            (point(0.0, 0.0, -5.0), vector(1.0, 1.0, 1.0)),
+        ];
+        for (origin, direction) in cases.iter() {
+            let c = Rc::new(Cylinder::default());
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert!(xs.is_empty());
+        }
+    }
+
+    #[test]
+    fn ray_strikes_cylinder() {
+        let cases = [
+            (point(1.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), 5.0, 5.0),
+            (point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), 4.0, 6.0),
+            (
+                point(0.5, 0.0, -5.0),
+                vector(0.1, 1.0, 1.0),
+                6.80798,
+                7.08872,
+            ),
+        ];
+        for (origin, direction, t0, t1) in cases.iter() {
+            let c = Rc::new(Cylinder::default());
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(2, xs.len());
+            assert!(eq_with_eps(*t0, xs[0].t));
+            assert!(eq_with_eps(*t1, xs[1].t));
+        }
+    }
+
+    #[test]
+    fn normal_vector_on_cylinder() {
+        let cases = [
+            (point(1.0, 0.0, 0.0), vector(1.0, 0.0, 0.0)),
+            (point(0.0, 5.0, -1.0), vector(0.0, 0.0, -1.0)),
+            (point(0.0, -2.0, 1.0), vector(0.0, 0.0, 1.0)),
+            (point(-1.0, 1.0, 0.0), vector(-1.0, 0.0, 0.0)),
+        ];
+        for (p, expected) in cases.iter() {
+            let c = Cylinder::default();
+            assert_eq!(*expected, c.local_normal_at(*p));
+        }
+    }
+
+    #[test]
+    fn default_minimum_and_maximum_for_cylinder() {
+        let c = Cylinder::default();
+        assert_eq!(f64::NEG_INFINITY, c.get_minimum());
+        assert_eq!(f64::INFINITY, c.get_maximum());
+    }
+
+    #[test]
+    fn intersecting_constrained_cylinder() {
+        let cases = [
+            (point(0.0, 1.5, 0.0), vector(0.1, 1.0, 0.0), 0),
+            (point(0.0, 3.0, -5.0), vector(0.0, 0.0, 1.0), 0),
+            (point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), 0),
+            (point(0.0, 2.0, -5.0), vector(0.0, 0.0, 1.0), 0),
+            (point(0.0, 1.0, -5.0), vector(0.0, 0.0, 1.0), 0),
+            (point(0.0, 1.5, -2.0), vector(0.0, 0.0, 1.0), 2),
+        ];
+        for (origin, direction, count) in cases.iter() {
+            let c = Rc::new(Cylinder::new(1.0, 2.0, false));
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(*count, xs.len());
+        }
+    }
+
+    #[test]
+    fn default_closed_value_for_cylinder() {
+        let c = Cylinder::default();
+        assert!(!c.is_closed());
+    }
+
+    #[test]
+    fn intersecting_caps_of_closed_cylinder() {
+        let cases = [
+            (point(0.0, 3.0, 0.0), vector(0.0, -1.0, 0.0), 2),
+            (point(0.0, 3.0, -2.0), vector(0.0, -1.0, 2.0), 2),
+            // corner case
+            (point(0.0, 4.0, -2.0), vector(0.0, -1.0, 1.0), 2),
+            (point(0.0, 0.0, -2.0), vector(0.0, 1.0, 2.0), 2),
+            // corner case
+            (point(0.0, -1.0, -2.0), vector(0.0, 1.0, 1.0), 2),
+        ];
+        for (origin, direction, count) in cases.iter() {
+            let c = Rc::new(Cylinder::new(1.0, 2.0, true));
+            let r = Ray {
+                origin: *origin,
+                direction: normalize(direction),
+            };
+            let xs = c.local_intersect(&r).unwrap();
+            assert_eq!(*count, xs.len());
+        }
+    }
+
+    #[test]
+    fn normal_vector_on_cylinders_end_caps() {
+        let cases = [
+            (point(0.0, 1.0, 0.0), vector(0.0, -1.0, 0.0)),
+            (point(0.5, 1.0, 0.0), vector(0.0, -1.0, 0.0)),
+            (point(0.0, 1.0, 0.5), vector(0.0, -1.0, 0.0)),
+            (point(0.0, 2.0, 0.0), vector(0.0, 1.0, 0.0)),
+            (point(0.5, 2.0, 0.0), vector(0.0, 1.0, 0.0)),
+            (point(0.0, 2.0, 0.5), vector(0.0, 1.0, 0.0)),
+        ];
+        for (p, expected) in cases.iter() {
+            let c = Cylinder::new(1.0, 2.0, true);
+            assert_eq!(*expected, c.local_normal_at(*p));
+        }
+    }
+
+    #[test]
+    fn bounded_cylinder_has_bounding_box() {
+        let c = Cylinder::new(-5.0, 3.0, false);
+        let b = c.bounds();
+        assert_eq!(point(-1.0, -5.0, -1.0), b.get_min());
+        assert_eq!(point(1.0, 3.0, 1.0), b.get_max());
+    }
+
+    #[test]
+    fn unbounded_cylinder_has_infinite_bounding_box() {
+        let b = Cylinder::default().bounds();
+        assert_eq!(f64::NEG_INFINITY, b.get_min().get_y());
+        assert_eq!(f64::INFINITY, b.get_max().get_y());
+        assert_eq!(1.0, b.get_max().get_x());
+    }
+}
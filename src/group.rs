@@ -0,0 +1,477 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{intersect, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{vector, Tuple},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+type Children = Vec<Rc<dyn Shape>>;
+
+/// Collection of shapes which is transformed as a single unit. Group's transformation is applied
+/// on top of transformations of its children; groups can be nested
+#[derive(Debug)]
+pub struct Group {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    children: Children,
+    bounds: BoundingBox,
+}
+
+impl Group {
+    pub fn add_child(&mut self, child: Rc<dyn Shape>) {
+        self.bounds.add_box(&child.parent_space_bounds());
+        self.children.push(child);
+    }
+
+    pub fn get_children(&self) -> &[Rc<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Moves children which fit entirely into one half of the group's bounds out of the group;
+    /// returns the children of the left and right half. Shapes with infinite bounds (like planes)
+    /// never fit, so the bounds are split around the finite children only
+    pub fn partition_children(&mut self) -> (Children, Children) {
+        let mut finite_bounds = BoundingBox::default();
+        for child in &self.children {
+            let b = child.parent_space_bounds();
+            if b.is_finite() {
+                finite_bounds.add_box(&b);
+            }
+        }
+        if finite_bounds.is_empty() {
+            return (vec![], vec![]);
+        }
+        let (left_bounds, right_bounds) = finite_bounds.split();
+
+        let mut left = vec![];
+        let mut right = vec![];
+        let mut remaining = vec![];
+        for child in self.children.drain(..) {
+            let b = child.parent_space_bounds();
+            if left_bounds.contains_box(&b) {
+                left.push(child);
+            } else if right_bounds.contains_box(&b) {
+                right.push(child);
+            } else {
+                remaining.push(child);
+            }
+        }
+        self.children = remaining;
+        (left, right)
+    }
+
+    /// Wraps given shapes in a new group and adds it as a child
+    pub fn make_subgroup(&mut self, children: Children) {
+        let mut subgroup = Group::default();
+        for child in children {
+            subgroup.add_child(child);
+        }
+        self.add_child(Rc::new(subgroup));
+    }
+}
+
+impl Shape for Group {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Group
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    /// Ray is already in group's space, so every child applies only its own transformation.
+    /// Intersections are reported for the children - group itself is never hit - so the group's
+    /// transformation is recorded in them, to get correct normals later
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        // none of the children can be hit if the ray misses the box around all of them
+        if !self.bounds.intersects(ray) {
+            return Ok(xs);
+        }
+        for child in &self.children {
+            let mut child_xs = intersect(child.clone(), ray)?;
+            for i in child_xs.iter_mut() {
+                i.parent_transform = self.transform * i.parent_transform;
+            }
+            xs.append(&mut child_xs);
+        }
+        xs.sort();
+        Ok(xs)
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.children.iter().any(|child| child.includes(other))
+    }
+
+    /// Group has no surface of its own - it is never hit directly, normals are computed for its
+    /// children - so its normal is zero vector
+    fn local_normal_at(&self, _object_point: Tuple) -> Tuple {
+        vector(0.0, 0.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Builds bounding volume hierarchy - groups with at least `threshold` children are split
+    /// into two subgroups, recursively. Children shared with other shapes are left intact
+    fn divide(&mut self, threshold: usize) {
+        let count = self.children.len();
+        if threshold <= count {
+            let (left, right) = self.partition_children();
+            if left.len() == count || right.len() == count {
+                // all children ended up on one side - they can't be separated any further
+                self.children = if left.is_empty() { right } else { left };
+            } else {
+                if !left.is_empty() {
+                    self.make_subgroup(left);
+                }
+                if !right.is_empty() {
+                    self.make_subgroup(right);
+                }
+            }
+        }
+        for child in self.children.iter_mut() {
+            if let Some(child) = Rc::get_mut(child) {
+                child.divide(threshold);
+            }
+        }
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Group {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            children: vec![],
+            bounds: BoundingBox::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cylinder::Cylinder,
+        intersections::{Computations, Intersection},
+        sphere::Sphere,
+        transformations::{rotation_y, scaling, translation},
+        tuple::point,
+        utils::{eq_with_eps, PI},
+    };
+
+    #[test]
+    fn creating_new_group() {
+        let g = Group::default();
+        assert_eq!(ShapeType::Group, g.get_shape_type());
+        assert_eq!(Matrix4::identity_matrix(), g.get_transform());
+        assert!(g.is_empty());
+    }
+
+    #[test]
+    fn group_has_no_normal_of_its_own() {
+        let g = Group::default();
+        assert_eq!(
+            vector(0.0, 0.0, 0.0),
+            g.normal_at(point(1.0, 0.0, 0.0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn adding_child_to_group() {
+        let mut g = Group::default();
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        g.add_child(s.clone());
+        assert!(!g.is_empty());
+        assert_eq!(s.get_id(), g.get_children()[0].get_id());
+    }
+
+    #[test]
+    fn group_includes_its_descendants() {
+        let s1: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let s2: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let mut inner = Group::default();
+        inner.add_child(s1.clone());
+        let mut g = Group::default();
+        g.add_child(Rc::new(inner));
+        assert!(g.includes(&*s1));
+        assert!(!g.includes(&*s2));
+    }
+
+    #[test]
+    fn intersecting_ray_with_empty_group() {
+        let g = Rc::new(Group::default());
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = g.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersecting_ray_with_nonempty_group() {
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::default();
+        s3.set_transform(translation(5.0, 0.0, 0.0));
+        let mut g = Group::default();
+        g.add_child(Rc::new(s1.clone()));
+        g.add_child(Rc::new(s2.clone()));
+        g.add_child(Rc::new(s3));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = Rc::new(g).local_intersect(&r).unwrap();
+        assert_eq!(4, xs.len());
+        assert_eq!(s2.get_id(), xs[0].object.borrow().get_id());
+        assert_eq!(s2.get_id(), xs[1].object.borrow().get_id());
+        assert_eq!(s1.get_id(), xs[2].object.borrow().get_id());
+        assert_eq!(s1.get_id(), xs[3].object.borrow().get_id());
+    }
+
+    #[test]
+    fn intersecting_transformed_group() {
+        let mut g = Group::default();
+        g.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        g.add_child(Rc::new(s));
+        let r = Ray {
+            origin: point(10.0, 0.0, -10.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = intersect(Rc::new(g), &r).unwrap();
+        assert_eq!(2, xs.len());
+        assert!(eq_with_eps(8.0, xs[0].t));
+        assert!(eq_with_eps(12.0, xs[1].t));
+    }
+
+    #[test]
+    fn intersection_records_transformations_of_nested_groups() {
+        let mut g1 = Group::default();
+        g1.set_transform(rotation_y(PI / 2.0));
+        let mut g2 = Group::default();
+        g2.set_transform(scaling(2.0, 2.0, 2.0));
+        g2.add_child(Rc::new(Sphere::default()));
+        g1.add_child(Rc::new(g2));
+        let r = Ray {
+            origin: point(-5.0, 0.0, 0.0),
+            direction: vector(1.0, 0.0, 0.0),
+        };
+        let xs = intersect(Rc::new(g1), &r).unwrap();
+        assert_eq!(2, xs.len());
+        assert_eq!(
+            rotation_y(PI / 2.0) * scaling(2.0, 2.0, 2.0),
+            xs[0].parent_transform
+        );
+    }
+
+    #[test]
+    fn finding_normal_on_child_object() {
+        let mut s = Sphere::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        let s = Rc::new(s);
+        let mut i = Intersection::new(1.0, s.clone());
+        i.parent_transform = rotation_y(PI / 2.0) * scaling(1.0, 2.0, 3.0);
+        let n = s.normal_at_hit(point(1.7321, 1.1547, -5.5774), &i).unwrap();
+        assert_eq!(vector(0.2857, 0.42854, -0.85716), n);
+    }
+
+    #[test]
+    fn preparing_computations_for_object_in_group() {
+        let mut g = Group::default();
+        g.set_transform(translation(0.0, 0.0, 5.0) * scaling(2.0, 2.0, 2.0));
+        g.add_child(Rc::new(Sphere::default()));
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = intersect(Rc::new(g), &r).unwrap();
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        assert_eq!(point(0.0, 0.0, 3.0), comps.point);
+        assert_eq!(vector(0.0, 0.0, -1.0), comps.normalv);
+    }
+
+    #[test]
+    fn group_has_bounding_box_containing_its_children() {
+        let mut s = Sphere::default();
+        s.set_transform(translation(2.0, 5.0, -3.0) * scaling(2.0, 2.0, 2.0));
+        let mut c = Cylinder::new(-2.0, 2.0, false);
+        c.set_transform(translation(-4.0, -1.0, 4.0) * scaling(0.5, 1.0, 0.5));
+        let mut g = Group::default();
+        g.add_child(Rc::new(s));
+        g.add_child(Rc::new(c));
+        let b = g.bounds();
+        assert_eq!(point(-4.5, -3.0, -5.0), b.get_min());
+        assert_eq!(point(4.0, 7.0, 4.5), b.get_max());
+    }
+
+    #[test]
+    fn intersecting_ray_and_group_does_not_test_children_if_box_is_missed() {
+        let mut g = Group::default();
+        g.add_child(Rc::new(Sphere::default()));
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        assert!(Rc::new(g).local_intersect(&r).unwrap().is_empty());
+    }
+
+    fn spheres_at(xs: &[f64]) -> Vec<Rc<dyn Shape>> {
+        xs.iter()
+            .map(|&x| {
+                let mut s = Sphere::default();
+                s.set_transform(translation(x, 0.0, 0.0));
+                Rc::new(s) as Rc<dyn Shape>
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partitioning_group_children() {
+        let shapes = spheres_at(&[-2.0, 2.0, 0.0]);
+        let mut g = Group::default();
+        for s in &shapes {
+            g.add_child(s.clone());
+        }
+        let (left, right) = g.partition_children();
+        assert_eq!(1, g.get_children().len());
+        assert_eq!(shapes[2].get_id(), g.get_children()[0].get_id());
+        assert_eq!(1, left.len());
+        assert_eq!(shapes[0].get_id(), left[0].get_id());
+        assert_eq!(1, right.len());
+        assert_eq!(shapes[1].get_id(), right[0].get_id());
+    }
+
+    #[test]
+    fn creating_subgroup_from_list_of_children() {
+        let shapes = spheres_at(&[0.0, 3.0]);
+        let mut g = Group::default();
+        g.make_subgroup(shapes.clone());
+        assert_eq!(1, g.get_children().len());
+        let subgroup = g.get_children()[0]
+            .as_any()
+            .downcast_ref::<Group>()
+            .unwrap();
+        assert_eq!(2, subgroup.get_children().len());
+        assert!(g.includes(&*shapes[1]));
+    }
+
+    #[test]
+    fn subdividing_group_partitions_its_children() {
+        let shapes = spheres_at(&[-2.0, 2.0, 0.0]);
+        let mut g = Group::default();
+        for s in &shapes {
+            g.add_child(s.clone());
+        }
+        g.divide(1);
+        let children = g.get_children();
+        assert_eq!(3, children.len());
+        assert_eq!(shapes[2].get_id(), children[0].get_id());
+        for (child, shape) in children[1..].iter().zip([&shapes[0], &shapes[1]].iter()) {
+            let subgroup = child.as_any().downcast_ref::<Group>().unwrap();
+            assert_eq!(1, subgroup.get_children().len());
+            assert_eq!(shape.get_id(), subgroup.get_children()[0].get_id());
+        }
+    }
+
+    #[test]
+    fn subdividing_group_with_too_few_children() {
+        let shapes = spheres_at(&[-2.0, 2.0, 0.0]);
+        let mut g = Group::default();
+        for s in &shapes {
+            g.add_child(s.clone());
+        }
+        g.divide(4);
+        assert_eq!(3, g.get_children().len());
+    }
+
+    #[test]
+    fn subdividing_group_keeps_identical_children_together() {
+        let shapes = spheres_at(&[1.0, 1.0, 1.0]);
+        let mut g = Group::default();
+        for s in &shapes {
+            g.add_child(s.clone());
+        }
+        g.divide(1);
+        assert_eq!(3, g.get_children().len());
+    }
+
+    #[test]
+    fn subdivided_group_is_hit_same_as_flat_one() {
+        let shapes = spheres_at(&[-6.0, -3.0, 0.0, 3.0, 6.0]);
+        let mut flat = Group::default();
+        let mut divided = Group::default();
+        for s in &shapes {
+            flat.add_child(s.clone());
+            divided.add_child(s.clone());
+        }
+        divided.divide(2);
+        let r = Ray {
+            origin: point(-10.0, 0.0, 0.0),
+            direction: vector(1.0, 0.0, 0.0),
+        };
+        let flat_xs = Rc::new(flat).local_intersect(&r).unwrap();
+        let divided_xs = Rc::new(divided).local_intersect(&r).unwrap();
+        assert_eq!(10, divided_xs.len());
+        for (a, b) in flat_xs.iter().zip(divided_xs.iter()) {
+            assert!(eq_with_eps(a.t, b.t));
+            assert_eq!(a.object.borrow().get_id(), b.object.borrow().get_id());
+        }
+    }
+}
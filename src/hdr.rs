@@ -0,0 +1,577 @@
+use crate::{canvas::Canvas, color::Color};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+};
+use thiserror::Error;
+
+/// Reasons why floating point image couldn't be turned into a canvas
+#[derive(Error, Debug)]
+pub enum HdrError {
+    #[error("Unsupported image format `{0}`")]
+    UnsupportedFormat(String),
+    #[error("Malformed header: invalid or missing {0}")]
+    MalformedHeader(&'static str),
+    #[error("Malformed run-length encoding in row {0}")]
+    MalformedRle(usize),
+    #[error("Truncated image data: expected {expected} bytes, found {found}")]
+    Truncated { expected: usize, found: usize },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Splits first `count` whitespace separated tokens off the data; returns them with the position
+/// of the single whitespace character which ends the last one
+fn header_tokens(data: &[u8], count: usize) -> Option<(Vec<&str>, usize)> {
+    let mut tokens = Vec::with_capacity(count);
+    let mut pos = 0;
+    while tokens.len() < count {
+        while data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let start = pos;
+        while !data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        tokens.push(std::str::from_utf8(&data[start..pos]).ok()?);
+    }
+    Some((tokens, pos))
+}
+
+/// Streams canvas as Portable Float Map - 32 bit floats, little endian, without any clamping.
+/// Rows are stored from the bottom of the image to the top, as the format requires
+pub fn write_pfm<W: Write>(c: &Canvas, out: &mut W) -> io::Result<()> {
+    // negative scale marks little endian data
+    write!(out, "PF\n{} {}\n-1.0\n", c.get_width(), c.get_height())?;
+    let mut row = Vec::with_capacity(c.get_width() * 12);
+    for pixels in (0..c.get_height()).rev().filter_map(|y| c.row(y)) {
+        row.clear();
+        for pixel in pixels {
+            for sample in [pixel.get_red(), pixel.get_green(), pixel.get_blue()].iter() {
+                row.extend_from_slice(&(*sample as f32).to_le_bytes());
+            }
+        }
+        out.write_all(&row)?;
+    }
+    out.flush()
+}
+
+pub fn save_pfm(c: &Canvas, path: &str) -> io::Result<()> {
+    write_pfm(c, &mut BufWriter::new(File::create(path)?))
+}
+
+/// Parses color (PF) or grayscale (Pf) Portable Float Map. Absolute value of the scale is
+/// ignored, as it carries no agreed meaning; its sign selects the byte order
+pub fn pfm_to_canvas(data: &[u8]) -> Result<Canvas, HdrError> {
+    let (tokens, pos) = header_tokens(data, 4).ok_or(HdrError::MalformedHeader("header"))?;
+    let channels = match tokens[0] {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => return Err(HdrError::UnsupportedFormat(magic.to_string())),
+    };
+    let dimension = |token: &str, name| match token.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(HdrError::MalformedHeader(name)),
+    };
+    let width = dimension(tokens[1], "width")?;
+    let height = dimension(tokens[2], "height")?;
+    let scale: f32 = match tokens[3].parse() {
+        Ok(s) if s != 0.0 => s,
+        _ => return Err(HdrError::MalformedHeader("scale")),
+    };
+
+    let body = &data[pos + 1..];
+    let expected = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels * 4))
+        .ok_or(HdrError::MalformedHeader("height"))?;
+    if body.len() < expected {
+        return Err(HdrError::Truncated {
+            expected,
+            found: body.len(),
+        });
+    }
+    let samples: Vec<f64> = body[..expected]
+        .chunks_exact(4)
+        .map(|b| {
+            let bytes = [b[0], b[1], b[2], b[3]];
+            if scale < 0.0 {
+                f32::from_le_bytes(bytes) as f64
+            } else {
+                f32::from_be_bytes(bytes) as f64
+            }
+        })
+        .collect();
+    let mut canvas = Canvas::new(width, height);
+    // rows are stored from the bottom of the image
+    let rows = samples.chunks_exact(width * channels).rev();
+    for (row, samples) in canvas.rows_mut().zip(rows) {
+        for (pixel, sample) in row.iter_mut().zip(samples.chunks_exact(channels)) {
+            *pixel = match sample {
+                [r, g, b] => Color::new(*r, *g, *b),
+                _ => Color::new(sample[0], sample[0], sample[0]),
+            };
+        }
+    }
+    Ok(canvas)
+}
+
+pub fn load_pfm(path: &str) -> Result<Canvas, HdrError> {
+    pfm_to_canvas(&fs::read(path)?)
+}
+
+/// Shared exponent encoding - mantissas of all channels scaled by the exponent of the brightest
+/// one. Negative channels can't be represented and become 0
+fn color_to_rgbe(c: &Color) -> [u8; 4] {
+    let (r, g, b) = (
+        c.get_red().max(0.0),
+        c.get_green().max(0.0),
+        c.get_blue().max(0.0),
+    );
+    let v = r.max(g).max(b);
+    if v < 1e-32 {
+        return [0; 4];
+    }
+    // v = m * 2^e, with m in [0.5, 1); exponent byte holds e + 128, so brighter values (including
+    // infinity) saturate at the largest exponent, with mantissas clamped to 255 by the casts
+    const MAX_EXPONENT: i32 = 127;
+    let mut e = (v.log2().floor() + 1.0).min(MAX_EXPONENT as f64) as i32;
+    if e < MAX_EXPONENT && v / 2f64.powi(e) >= 1.0 {
+        e += 1;
+    }
+    let scale = 256.0 / 2f64.powi(e);
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (e + 128) as u8,
+    ]
+}
+
+fn rgbe_to_color(rgbe: &[u8]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let f = 2f64.powi(rgbe[3] as i32 - (128 + 8));
+    Color::new(rgbe[0] as f64 * f, rgbe[1] as f64 * f, rgbe[2] as f64 * f)
+}
+
+/// Run-length encodes one component of a scanline: runs of at least 4 equal bytes are stored as
+/// (128 + length, byte), everything else as (length, bytes...)
+fn encode_rle(data: &[u8], out: &mut Vec<u8>) {
+    const MIN_RUN: usize = 4;
+    let mut pos = 0;
+    while pos < data.len() {
+        // find the start of the next long enough run
+        let mut run_start = pos;
+        let mut run_length = 0;
+        while run_start < data.len() {
+            run_length = data[run_start..]
+                .iter()
+                .take(127)
+                .take_while(|b| **b == data[run_start])
+                .count();
+            if run_length >= MIN_RUN {
+                break;
+            }
+            run_start += run_length;
+        }
+        if run_length < MIN_RUN {
+            run_start = data.len();
+        }
+        for literals in data[pos..run_start].chunks(128) {
+            out.push(literals.len() as u8);
+            out.extend_from_slice(literals);
+        }
+        if run_start < data.len() {
+            out.push(128 + run_length as u8);
+            out.push(data[run_start]);
+        }
+        pos = run_start + run_length;
+    }
+}
+
+/// Streams canvas as Radiance RGBE image. Scanlines are run-length encoded whenever the width
+/// allows it (8 to 32767 pixels), otherwise stored flat
+pub fn write_hdr<W: Write>(c: &Canvas, out: &mut W) -> io::Result<()> {
+    let (width, height) = (c.get_width(), c.get_height());
+    write!(
+        out,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        height, width
+    )?;
+    let rle = (8..=0x7fff).contains(&width);
+    let mut pixels = Vec::with_capacity(width);
+    let mut row = Vec::with_capacity(width * 4 + 4);
+    for colors in c.rows() {
+        pixels.clear();
+        pixels.extend(colors.iter().map(color_to_rgbe));
+        row.clear();
+        if rle {
+            row.extend_from_slice(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8]);
+            for component in 0..4 {
+                let bytes: Vec<u8> = pixels.iter().map(|p| p[component]).collect();
+                encode_rle(&bytes, &mut row);
+            }
+        } else {
+            row.extend(pixels.iter().flatten());
+        }
+        out.write_all(&row)?;
+    }
+    out.flush()
+}
+
+pub fn save_hdr(c: &Canvas, path: &str) -> io::Result<()> {
+    write_hdr(c, &mut BufWriter::new(File::create(path)?))
+}
+
+/// Reads run-length encoded scanline starting after its 4 byte marker; returns RGBE pixels and
+/// the number of bytes consumed
+fn decode_rle_scanline(
+    data: &[u8],
+    width: usize,
+    row: usize,
+) -> Result<(Vec<[u8; 4]>, usize), HdrError> {
+    let mut pixels = vec![[0u8; 4]; width];
+    let mut pos = 0;
+    let byte = |pos: usize| data.get(pos).copied().ok_or(HdrError::MalformedRle(row));
+    for component in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let count = byte(pos)? as usize;
+            pos += 1;
+            if count > 128 {
+                let count = count - 128;
+                let value = byte(pos)?;
+                pos += 1;
+                if x + count > width {
+                    return Err(HdrError::MalformedRle(row));
+                }
+                for p in pixels[x..x + count].iter_mut() {
+                    p[component] = value;
+                }
+                x += count;
+            } else {
+                if count == 0 || x + count > width {
+                    return Err(HdrError::MalformedRle(row));
+                }
+                for p in pixels[x..x + count].iter_mut() {
+                    p[component] = byte(pos)?;
+                    pos += 1;
+                }
+                x += count;
+            }
+        }
+    }
+    Ok((pixels, pos))
+}
+
+/// Parses Radiance RGBE image with standard orientation (-Y height +X width), either flat or
+/// with run-length encoded scanlines
+pub fn hdr_to_canvas(data: &[u8]) -> Result<Canvas, HdrError> {
+    let mut lines = data.split(|b| *b == b'\n');
+    let mut pos = 0;
+    let mut next_line = || {
+        let line = lines.next()?;
+        pos += line.len() + 1;
+        Some(String::from_utf8_lossy(line).into_owned())
+    };
+    let magic = next_line().ok_or(HdrError::MalformedHeader("magic number"))?;
+    if !magic.starts_with("#?") {
+        return Err(HdrError::UnsupportedFormat(magic));
+    }
+    loop {
+        let line = next_line().ok_or(HdrError::MalformedHeader("end of header"))?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(HdrError::UnsupportedFormat(format.to_string()));
+            }
+        }
+    }
+    let resolution = next_line().ok_or(HdrError::MalformedHeader("resolution"))?;
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", h, "+X", w] => match (h.parse::<usize>(), w.parse::<usize>()) {
+            (Ok(h), Ok(w)) if h > 0 && w > 0 => (h, w),
+            _ => return Err(HdrError::MalformedHeader("resolution")),
+        },
+        _ => return Err(HdrError::UnsupportedFormat(resolution)),
+    };
+
+    let mut body = data.get(pos..).unwrap_or(&[]);
+    let flat_size = width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(4))
+        .ok_or(HdrError::MalformedHeader("resolution"))?;
+    let rle_allowed = (8..=0x7fff).contains(&width);
+    // header can't be trusted with allocating the canvas - flat images are checked up front,
+    // run-length encoded ones are decoded before the canvas is created
+    if !rle_allowed && body.len() < flat_size {
+        return Err(HdrError::Truncated {
+            expected: flat_size,
+            found: body.len(),
+        });
+    }
+    let mut colors = Vec::new();
+    for y in 0..height {
+        let rle =
+            rle_allowed && body.len() >= 4 && body[0] == 2 && body[1] == 2 && body[2] & 0x80 == 0;
+        let pixels = if rle {
+            if (body[2] as usize) << 8 | body[3] as usize != width {
+                return Err(HdrError::MalformedRle(y));
+            }
+            let (pixels, used) = decode_rle_scanline(&body[4..], width, y)?;
+            body = &body[4 + used..];
+            pixels
+        } else {
+            let expected = width * 4;
+            if body.len() < expected {
+                return Err(HdrError::Truncated {
+                    expected,
+                    found: body.len(),
+                });
+            }
+            let pixels = body[..expected]
+                .chunks_exact(4)
+                .map(|p| [p[0], p[1], p[2], p[3]])
+                .collect();
+            body = &body[expected..];
+            pixels
+        };
+        colors.extend(pixels.iter().map(|rgbe| rgbe_to_color(rgbe)));
+    }
+    let mut canvas = Canvas::new(width, height);
+    canvas.as_mut_slice().copy_from_slice(&colors);
+    Ok(canvas)
+}
+
+pub fn load_hdr(path: &str) -> Result<Canvas, HdrError> {
+    hdr_to_canvas(&fs::read(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Canvas with values outside of [0, 1], which must survive the round trip
+    fn bright_canvas(width: usize, height: usize) -> Canvas {
+        let mut c = Canvas::new(width, height);
+        for x in 0..width {
+            for y in 0..height {
+                let color = Color::new(x as f64 * 4.0, 0.5 + y as f64, 1.0 / (x + 1) as f64);
+                c.write_pixel(x, y, color).unwrap();
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn writing_pfm() {
+        let mut c = Canvas::new(1, 2);
+        c.write_pixel(0, 0, Color::new(10.0, 0.0, -1.0)).unwrap();
+        let mut out = Vec::new();
+        write_pfm(&c, &mut out).unwrap();
+        let mut expected = b"PF\n1 2\n-1.0\n".to_vec();
+        // bottom row first
+        expected.extend_from_slice(&[0; 12]);
+        for sample in [10.0f32, 0.0, -1.0].iter() {
+            expected.extend_from_slice(&sample.to_le_bytes());
+        }
+        assert_eq!(expected, out);
+    }
+
+    #[test]
+    fn pfm_round_trip_keeps_values_above_one() {
+        let c = bright_canvas(3, 2);
+        let mut out = Vec::new();
+        write_pfm(&c, &mut out).unwrap();
+        let read = pfm_to_canvas(&out).unwrap();
+        for x in 0..3 {
+            for y in 0..2 {
+                assert_eq!(c.pixel_at(x, y).unwrap(), read.pixel_at(x, y).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn reading_big_endian_grayscale_pfm() {
+        let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+        data.extend_from_slice(&2.5f32.to_be_bytes());
+        data.extend_from_slice(&0.25f32.to_be_bytes());
+        let c = pfm_to_canvas(&data).unwrap();
+        assert_eq!(Color::new(2.5, 2.5, 2.5), c.pixel_at(0, 0).unwrap());
+        assert_eq!(Color::new(0.25, 0.25, 0.25), c.pixel_at(1, 0).unwrap());
+    }
+
+    #[test]
+    fn malformed_pfm_is_reported() {
+        assert!(matches!(
+            pfm_to_canvas(b"P6\n1 1\n255\n"),
+            Err(HdrError::UnsupportedFormat(magic)) if magic == "P6"
+        ));
+        assert!(matches!(
+            pfm_to_canvas(b"PF\n1 1\n0\n"),
+            Err(HdrError::MalformedHeader("scale"))
+        ));
+        assert!(matches!(
+            pfm_to_canvas(b"PF\n1 1\n-1.0\n\0\0\0\0"),
+            Err(HdrError::Truncated {
+                expected: 12,
+                found: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn huge_pfm_dimensions_are_reported() {
+        assert!(matches!(
+            pfm_to_canvas(b"PF\n18446744073709551615 2\n-1.0\n"),
+            Err(HdrError::MalformedHeader("height"))
+        ));
+        assert!(matches!(
+            pfm_to_canvas(b"PF\n200000 200000\n-1.0\n"),
+            Err(HdrError::Truncated { found: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn converting_colors_to_rgbe() {
+        let cases = [
+            (Color::new(1.0, 0.5, 0.25), [128, 64, 32, 129]),
+            (Color::new(0.0, 0.0, 0.0), [0, 0, 0, 0]),
+            (Color::new(-1.0, 3.0, 0.0), [0, 192, 0, 130]),
+        ];
+        for (color, rgbe) in cases.iter() {
+            assert_eq!(*rgbe, color_to_rgbe(color));
+        }
+        assert_eq!(Color::new(0.0, 3.0, 0.0), rgbe_to_color(&[0, 192, 0, 130]));
+    }
+
+    #[test]
+    fn values_too_bright_for_rgbe_saturate() {
+        let cases = [
+            (Color::new(f64::INFINITY, 0.0, 1.0), [255, 0, 0, 255]),
+            (Color::new(2f64.powi(126), 0.0, 0.0), [128, 0, 0, 255]),
+            (Color::new(2f64.powi(127), 0.0, 0.0), [255, 0, 0, 255]),
+            (
+                Color::new(2f64.powi(200), 2f64.powi(126), 0.0),
+                [255, 128, 0, 255],
+            ),
+        ];
+        for (color, rgbe) in cases.iter() {
+            assert_eq!(*rgbe, color_to_rgbe(color));
+        }
+        let c = Canvas::new_with_color(10, 1, Color::new(f64::INFINITY, 1.0, 0.0));
+        let mut out = Vec::new();
+        write_hdr(&c, &mut out).unwrap();
+        let read = hdr_to_canvas(&out).unwrap();
+        assert!(read.pixel_at(0, 0).unwrap().get_red() > 1e38);
+    }
+
+    #[test]
+    fn run_length_encoding_scanline() {
+        let mut out = Vec::new();
+        encode_rle(&[1, 2, 3, 3, 3, 3, 3, 4, 5, 5], &mut out);
+        assert_eq!(vec![2, 1, 2, 128 + 5, 3, 3, 4, 5, 5], out);
+        let (pixels, used) = decode_rle_scanline(&[1, 9, 1, 8, 1, 7, 1, 6], 1, 0).unwrap();
+        assert_eq!(vec![[9, 8, 7, 6]], pixels);
+        assert_eq!(8, used);
+    }
+
+    #[test]
+    fn writing_hdr_header() {
+        let mut out = Vec::new();
+        write_hdr(&Canvas::new(2, 3), &mut out).unwrap();
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 3 +X 2\n";
+        assert_eq!(&header[..], &out[..header.len()]);
+        // narrow scanlines are stored flat
+        assert_eq!(header.len() + 3 * 2 * 4, out.len());
+    }
+
+    #[test]
+    fn hdr_round_trip_keeps_values_above_one() {
+        for width in [3, 20].iter() {
+            let c = bright_canvas(*width, 4);
+            let mut out = Vec::new();
+            write_hdr(&c, &mut out).unwrap();
+            let read = hdr_to_canvas(&out).unwrap();
+            for x in 0..*width {
+                for y in 0..4 {
+                    let expected = c.pixel_at(x, y).unwrap();
+                    let actual = read.pixel_at(x, y).unwrap();
+                    // mantissa has 8 bits, relative to the brightest channel
+                    let tolerance = expected
+                        .get_red()
+                        .max(expected.get_green())
+                        .max(expected.get_blue())
+                        / 128.0;
+                    assert!((expected.get_red() - actual.get_red()).abs() <= tolerance);
+                    assert!((expected.get_green() - actual.get_green()).abs() <= tolerance);
+                    assert!((expected.get_blue() - actual.get_blue()).abs() <= tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn run_length_encoding_shrinks_flat_images() {
+        let c = Canvas::new_with_color(100, 10, Color::new(2.0, 1.0, 0.5));
+        let mut out = Vec::new();
+        write_hdr(&c, &mut out).unwrap();
+        assert!(out.len() < 100 * 10 * 4 / 4);
+        let read = hdr_to_canvas(&out).unwrap();
+        assert_eq!(Color::new(2.0, 1.0, 0.5), read.pixel_at(99, 9).unwrap());
+    }
+
+    #[test]
+    fn malformed_hdr_is_reported() {
+        assert!(matches!(
+            hdr_to_canvas(b"P3\n"),
+            Err(HdrError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n"),
+            Err(HdrError::UnsupportedFormat(format)) if format == "32-bit_rle_xyze"
+        ));
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n+X 1 -Y 1\n"),
+            Err(HdrError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n-Y 1 +X 2\n\x01\x02\x03\x04"),
+            Err(HdrError::Truncated {
+                expected: 8,
+                found: 4
+            })
+        ));
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n-Y 1 +X 8\n\x02\x02\x00\x08\x85\x01"),
+            Err(HdrError::MalformedRle(0))
+        ));
+    }
+
+    #[test]
+    fn huge_hdr_resolution_is_reported_without_allocating() {
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n-Y 18446744073709551615 +X 2\n"),
+            Err(HdrError::MalformedHeader("resolution"))
+        ));
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n-Y 200000 +X 200000\n"),
+            Err(HdrError::Truncated {
+                expected: 160_000_000_000,
+                found: 0
+            })
+        ));
+        // width allowing run-length encoding - rows are decoded before allocating
+        assert!(matches!(
+            hdr_to_canvas(b"#?RADIANCE\n\n-Y 200000 +X 20000\n"),
+            Err(HdrError::Truncated {
+                expected: 80_000,
+                found: 0
+            })
+        ));
+    }
+}
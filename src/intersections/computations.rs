@@ -1,19 +1,33 @@
 use crate::{
-    intersections::Intersection,
-    matrix::MatrixError,
+    intersections::{Intersection, Intersections},
+    matrix::{Matrix4, MatrixError},
     ray::Ray,
     shape::Shape,
-    tuple::{dot, Tuple},
+    tuple::{dot, reflect, Tuple},
+    utils::EPSILON,
 };
 use std::{cell::RefCell, rc::Rc};
 
 pub struct Computations {
     pub t: f64,
     pub object: RefCell<Rc<dyn Shape>>,
+    /// Transformation of groups the object is nested in, copied from the intersection
+    pub parent_transform: Matrix4,
     pub point: Tuple,
+    /// Point slightly above the surface, used to avoid self-shadowing caused by floating point
+    /// inaccuracy (so called "acne")
+    pub over_point: Tuple,
+    /// Point slightly below the surface, where refracted rays originate
+    pub under_point: Tuple,
     pub eyev: Tuple,
     pub normalv: Tuple,
+    /// Direction of the ray reflected off the surface
+    pub reflectv: Tuple,
     pub inside: bool,
+    /// Refractive indices of the materials on both sides of the surface - the ray passes from
+    /// n1 to n2
+    pub n1: f64,
+    pub n2: f64,
 }
 
 impl Computations {
@@ -21,35 +35,106 @@ impl Computations {
         dot(normalv, eyev) < 0.0
     }
 
+    /// Walks through all intersections of the ray and keeps track of objects the ray is inside
+    /// of; refractive index on either side of the hit belongs to the most recently entered object,
+    /// or is 1.0 (vacuum) when the ray isn't inside anything
+    fn refractive_indices(hit: &Intersection, xs: &Intersections) -> (f64, f64) {
+        let mut containers: Vec<Rc<dyn Shape>> = vec![];
+        let mut n1 = 1.0;
+        let mut n2 = 1.0;
+        let last_index = |containers: &Vec<Rc<dyn Shape>>| {
+            containers
+                .last()
+                .map_or(1.0, |o| o.get_material().refractive_index)
+        };
+        for i in xs.iter() {
+            let is_hit = i == hit;
+            if is_hit {
+                n1 = last_index(&containers);
+            }
+            let object = i.object.borrow().clone();
+            match containers
+                .iter()
+                .position(|o| o.get_id() == object.get_id())
+            {
+                Some(index) => {
+                    containers.remove(index);
+                }
+                None => containers.push(object),
+            }
+            if is_hit {
+                n2 = last_index(&containers);
+                break;
+            }
+        }
+        (n1, n2)
+    }
+
+    /// `xs` are all intersections of the ray, sorted - they are needed to find out which
+    /// materials the ray passes between at the hit
     pub fn prepare_computation(
         intersection: Intersection,
         ray: Ray,
+        xs: &Intersections,
     ) -> Result<Computations, MatrixError> {
         let t = intersection.t;
         let ray_position = ray.position(t);
         let eyev = -ray.direction;
-        let mut normalv = intersection.object.borrow().normal_at(ray_position)?;
+        let mut normalv = intersection
+            .object
+            .borrow()
+            .normal_at_hit(ray_position, &intersection)?;
         let inside = Self::is_inside(&eyev, &normalv);
         if inside {
             normalv = -normalv;
         };
+        let reflectv = reflect(&ray.direction, &normalv);
+        let (n1, n2) = Self::refractive_indices(&intersection, xs);
         Ok(Computations {
             t,
             object: intersection.object.clone(),
+            parent_transform: intersection.parent_transform,
             point: ray_position,
+            over_point: ray_position + normalv * EPSILON,
+            under_point: ray_position - normalv * EPSILON,
             eyev,
             normalv,
+            reflectv,
             inside,
+            n1,
+            n2,
         })
     }
+
+    /// Schlick's approximation of Fresnel equations - fraction of light reflected off the
+    /// surface; the rest is refracted. The more grazing the angle, the more light is reflected
+    pub fn schlick(&self) -> f64 {
+        // cosine of the angle between the eye and normal vector
+        let mut cos = dot(&self.eyev, &self.normalv);
+        // total internal reflection can only occur if n1 > n2
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            // when n1 > n2, use cos(theta_t) instead
+            cos = (1.0 - sin2_t).sqrt();
+        }
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        sphere::Sphere,
-        tuple::{point, vector},
+        material::Material,
+        plane::Plane,
+        sphere::{glass_sphere, Sphere},
+        transformations::{scaling, translation},
+        tuple::{point, vector, TupleT},
         utils::eq_with_eps,
     };
 
@@ -60,11 +145,10 @@ mod tests {
             direction: vector(0.0, 0.0, 1.0),
         };
         let shape = Sphere::default();
-        let i = Intersection {
-            t: 4.0,
-            object: RefCell::new(Rc::new(shape)),
-        };
-        let comps = Computations::prepare_computation(i.clone(), r).unwrap();
+        let i = Intersection::new(4.0, Rc::new(shape));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i.clone()]))
+                .unwrap();
         assert!(eq_with_eps(i.t, comps.t));
         assert_eq!(i.object.borrow().get_id(), comps.object.borrow().get_id());
         assert_eq!(point(0.0, 0.0, -1.0), comps.point);
@@ -79,12 +163,10 @@ mod tests {
             direction: vector(0.0, 0.0, 1.0),
         };
         let shape = Sphere::default();
-        let i = Intersection {
-            t: 4.0,
-            object: RefCell::new(Rc::new(shape)),
-        };
-        let comps = Computations::prepare_computation(i, r).unwrap();
-        assert_eq!(false, comps.inside);
+        let i = Intersection::new(4.0, Rc::new(shape));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        assert!(!comps.inside);
     }
 
     #[test]
@@ -94,14 +176,144 @@ mod tests {
             direction: vector(0.0, 0.0, 1.0),
         };
         let shape = Sphere::default();
-        let i = Intersection {
-            t: 1.0,
-            object: RefCell::new(Rc::new(shape)),
-        };
-        let comps = Computations::prepare_computation(i, r).unwrap();
+        let i = Intersection::new(1.0, Rc::new(shape));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
         assert_eq!(point(0.0, 0.0, 1.0), comps.point);
         assert_eq!(vector(0.0, 0.0, -1.0), comps.eyev);
         assert_eq!(vector(0.0, 0.0, -1.0), comps.normalv);
-        assert_eq!(true, comps.inside);
+        assert!(comps.inside);
+    }
+
+    #[test]
+    fn hit_should_offset_the_point() {
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let mut shape = Sphere::default();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, Rc::new(shape));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        assert!(comps.over_point.get_z() < -EPSILON / 2.0);
+        assert!(comps.point.get_z() > comps.over_point.get_z());
+    }
+
+    #[test]
+    fn precomputing_reflection_vector() {
+        let shape = Plane::default();
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 1.0, -1.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let i = Intersection::new(2.0f64.sqrt(), Rc::new(shape));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        assert_eq!(vector(0.0, half_sqrt, half_sqrt), comps.reflectv);
+    }
+
+    #[test]
+    fn finding_n1_and_n2_at_various_intersections() {
+        let mut a = glass_sphere();
+        a.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut b = glass_sphere();
+        b.set_transform(translation(0.0, 0.0, -0.25));
+        b.set_material(Material {
+            refractive_index: 2.0,
+            ..b.get_material().clone()
+        });
+        let mut c = glass_sphere();
+        c.set_transform(translation(0.0, 0.0, 0.25));
+        c.set_material(Material {
+            refractive_index: 2.5,
+            ..c.get_material().clone()
+        });
+        let (a, b, c): (Rc<dyn Shape>, Rc<dyn Shape>, Rc<dyn Shape>) =
+            (Rc::new(a), Rc::new(b), Rc::new(c));
+        let r = Ray {
+            origin: point(0.0, 0.0, -4.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(2.0, a.clone()),
+            Intersection::new(2.75, b.clone()),
+            Intersection::new(3.25, c.clone()),
+            Intersection::new(4.75, b),
+            Intersection::new(5.25, c),
+            Intersection::new(6.0, a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        for (i, (n1, n2)) in xs.iter().zip(expected.iter()) {
+            let comps = Computations::prepare_computation(i.clone(), r, &xs).unwrap();
+            assert!(eq_with_eps(*n1, comps.n1));
+            assert!(eq_with_eps(*n2, comps.n2));
+        }
+    }
+
+    #[test]
+    fn under_point_is_offset_below_the_surface() {
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let mut shape = glass_sphere();
+        shape.set_transform(translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, Rc::new(shape));
+        let xs = Intersections::from(vec![i.clone()]);
+        let comps = Computations::prepare_computation(i, r, &xs).unwrap();
+        assert!(comps.under_point.get_z() > EPSILON / 2.0);
+        assert!(comps.point.get_z() < comps.under_point.get_z());
+    }
+
+    #[test]
+    fn schlick_approximation_under_total_internal_reflection() {
+        let shape: Rc<dyn Shape> = Rc::new(glass_sphere());
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, half_sqrt),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(-half_sqrt, shape.clone()),
+            Intersection::new(half_sqrt, shape),
+        ]);
+        let comps = Computations::prepare_computation(xs[1].clone(), r, &xs).unwrap();
+        assert!(eq_with_eps(1.0, comps.schlick()));
+    }
+
+    #[test]
+    fn schlick_approximation_with_perpendicular_viewing_angle() {
+        let shape: Rc<dyn Shape> = Rc::new(glass_sphere());
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(-1.0, shape.clone()),
+            Intersection::new(1.0, shape),
+        ]);
+        let comps = Computations::prepare_computation(xs[1].clone(), r, &xs).unwrap();
+        assert!(eq_with_eps(0.04, comps.schlick()));
+    }
+
+    #[test]
+    fn schlick_approximation_with_small_angle_and_n2_greater_than_n1() {
+        let shape: Rc<dyn Shape> = Rc::new(glass_sphere());
+        let r = Ray {
+            origin: point(0.0, 0.99, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = Intersections::from(vec![Intersection::new(1.8589, shape)]);
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        assert!((0.48873 - comps.schlick()).abs() < 0.0001);
     }
 }
@@ -1,8 +1,7 @@
 use crate::{
-    matrix::MatrixError,
+    matrix::{Matrix4, MatrixError},
     ray::{transform, Ray},
     shape::Shape,
-    tuple::{dot, point},
     utils::eq_with_eps,
 };
 use std::{
@@ -16,6 +15,29 @@ use std::{
 pub struct Intersection {
     pub t: f64,
     pub object: RefCell<Rc<dyn Shape>>,
+    /// Barycentric coordinates of the intersection; set only for triangles, where they are used
+    /// to interpolate the normal
+    pub u: f64,
+    pub v: f64,
+    /// Combined transformation of all groups the object is nested in (world to object chain);
+    /// identity for objects placed directly in the world
+    pub parent_transform: Matrix4,
+}
+
+impl Intersection {
+    pub fn new(t: f64, object: Rc<dyn Shape>) -> Intersection {
+        Self::new_with_uv(t, object, 0.0, 0.0)
+    }
+
+    pub fn new_with_uv(t: f64, object: Rc<dyn Shape>, u: f64, v: f64) -> Intersection {
+        Intersection {
+            t,
+            object: RefCell::new(object),
+            u,
+            v,
+            parent_transform: Matrix4::identity_matrix(),
+        }
+    }
 }
 
 impl PartialEq for Intersection {
@@ -67,12 +89,15 @@ impl Intersections {
 
     pub fn hit(&mut self) -> Option<&Intersection> {
         self.sort();
-        for intersection in &self.0 {
-            if intersection.t > 0.0 || eq_with_eps(intersection.t, 0.0) {
-                return Some(intersection);
-            }
-        }
-        None
+        self.0
+            .iter()
+            .find(|intersection| intersection.t > 0.0 || eq_with_eps(intersection.t, 0.0))
+    }
+}
+
+impl From<Vec<Intersection>> for Intersections {
+    fn from(intersections: Vec<Intersection>) -> Self {
+        Intersections(intersections)
     }
 }
 
@@ -87,33 +112,10 @@ impl Default for Intersections {
 /// In example instead of making sphere two times bigger, you shrink the distance between ray and
 /// the sphere
 /// In order to calculate proper intersection on scaled object, you need to apply inverse of
-/// sphere's transformation onto ray
+/// object's transformation onto ray; the shape itself only knows how to intersect in object space
 pub fn intersect(object: Rc<dyn Shape>, ray: &Ray) -> Result<Intersections, MatrixError> {
-    let ray2 = transform(*ray, object.get_transform().inverse()?);
-    // Vector from the sphere's center to the ray origin
-    let sphere_to_ray = ray2.origin - point(0.0, 0.0, 0.0);
-    let a = dot(&ray2.direction, &ray2.direction);
-    let b = 2.0 * dot(&ray2.direction, &sphere_to_ray);
-    let c = dot(&sphere_to_ray, &sphere_to_ray) - 1.0;
-    let discriminant = (b * b) - (4.0 * a * c);
-
-    if !eq_with_eps(discriminant, 0.0) && discriminant < 0.0 {
-        Ok(Intersections(vec![]))
-    } else {
-        let sqrt_discriminant = discriminant.sqrt();
-        let t1 = (-b - sqrt_discriminant) / (2.0 * a);
-        let t2 = (-b + sqrt_discriminant) / (2.0 * a);
-        Ok(Intersections(vec![
-            Intersection {
-                t: t1,
-                object: RefCell::new(object.clone()),
-            },
-            Intersection {
-                t: t2,
-                object: RefCell::new(object.clone()),
-            },
-        ]))
-    }
+    let local_ray = transform(*ray, object.get_transform().inverse()?);
+    object.local_intersect(&local_ray)
 }
 
 #[cfg(test)]
@@ -121,28 +123,27 @@ mod tests {
     use super::*;
     use crate::sphere::Sphere;
 
+    #[test]
+    fn intersection_can_encapsulate_u_and_v() {
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i = Intersection::new_with_uv(3.5, s.clone(), 0.2, 0.4);
+        assert!(eq_with_eps(0.2, i.u));
+        assert!(eq_with_eps(0.4, i.v));
+    }
+
     #[test]
     fn intersection_encapsulates_t_and_object() {
-        let s = Sphere::default();
-        let i = Intersection {
-            t: 3.5,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i = Intersection::new(3.5, s.clone());
         assert!(eq_with_eps(3.5, i.t));
         assert_eq!(s.get_id(), i.object.borrow().get_id());
     }
 
     #[test]
     fn aggregating_intersections() {
-        let s = Sphere::default();
-        let i1 = Intersection {
-            t: 1.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i2 = Intersection {
-            t: 2.0,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i1 = Intersection::new(1.0, s.clone());
+        let i2 = Intersection::new(2.0, s.clone());
         let xs = Intersections(vec![i1, i2]);
         assert_eq!(2, xs.0.len());
         assert!(eq_with_eps(1.0, xs.0[0].t));
@@ -151,15 +152,9 @@ mod tests {
 
     #[test]
     fn hit_when_all_intersections_have_positive_t() {
-        let s = Sphere::default();
-        let i1 = Intersection {
-            t: 1.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i2 = Intersection {
-            t: 2.0,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i1 = Intersection::new(1.0, s.clone());
+        let i2 = Intersection::new(2.0, s.clone());
         let mut xs = Intersections(vec![i2, i1.clone()]);
         let i = xs.hit();
         assert_eq!(&i1, i.unwrap());
@@ -167,15 +162,9 @@ mod tests {
 
     #[test]
     fn hit_when_some_intersections_have_negative_t() {
-        let s = Sphere::default();
-        let i1 = Intersection {
-            t: -1.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i2 = Intersection {
-            t: 2.0,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i1 = Intersection::new(-1.0, s.clone());
+        let i2 = Intersection::new(2.0, s.clone());
         let mut xs = Intersections(vec![i2.clone(), i1]);
         let i = xs.hit();
         assert_eq!(&i2, i.unwrap());
@@ -183,15 +172,9 @@ mod tests {
 
     #[test]
     fn hit_when_all_intersections_have_negative_t() {
-        let s = Sphere::default();
-        let i1 = Intersection {
-            t: -2.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i2 = Intersection {
-            t: -1.0,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i1 = Intersection::new(-2.0, s.clone());
+        let i2 = Intersection::new(-1.0, s.clone());
         let mut xs = Intersections(vec![i2, i1]);
         let i = xs.hit();
         assert_eq!(None, i);
@@ -199,23 +182,11 @@ mod tests {
 
     #[test]
     fn hit_when_is_always_the_lowest_nonnegative_intersection() {
-        let s = Sphere::default();
-        let i1 = Intersection {
-            t: 5.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i2 = Intersection {
-            t: 7.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i3 = Intersection {
-            t: -3.0,
-            object: RefCell::new(Rc::new(s)),
-        };
-        let i4 = Intersection {
-            t: 2.0,
-            object: RefCell::new(Rc::new(s)),
-        };
+        let s: Rc<dyn Shape> = Rc::new(Sphere::default());
+        let i1 = Intersection::new(5.0, s.clone());
+        let i2 = Intersection::new(7.0, s.clone());
+        let i3 = Intersection::new(-3.0, s.clone());
+        let i4 = Intersection::new(2.0, s.clone());
         let mut xs = Intersections(vec![i1, i2, i3, i4.clone()]);
         let i = xs.hit();
         assert_eq!(&i4, i.unwrap());
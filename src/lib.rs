@@ -1,21 +1,38 @@
 #![feature(iter_order_by)]
 
 pub use self::{
-    canvas::*, color::*, intersections::*, light::*, material::*, matrix::*, ray::*, serialize::*,
-    shape::*, sphere::*, transformations::*, tuple::*, utils::*, world::*,
+    bounds::*, camera::*, canvas::*, color::*, cone::*, csg::*, cube::*, cylinder::*, group::*,
+    hdr::*, intersections::*, light::*, material::*, matrix::*, noise::*, obj_file::*, patterns::*,
+    plane::*, png::*, ray::*, shape::*, smooth_triangle::*, sphere::*, tone_mapping::*,
+    transformations::*, triangle::*, tuple::*, utils::*, world::*,
 };
 
+mod bounds;
+mod camera;
 mod canvas;
 mod color;
+mod cone;
+mod csg;
+mod cube;
+mod cylinder;
+mod group;
+mod hdr;
 mod intersections;
 mod light;
 mod material;
 mod matrix;
+mod noise;
+mod obj_file;
+mod patterns;
+mod plane;
+mod png;
 mod ray;
-mod serialize;
 mod shape;
+mod smooth_triangle;
 mod sphere;
+mod tone_mapping;
 mod transformations;
+mod triangle;
 mod tuple;
 mod utils;
 mod world;
@@ -1,60 +1,102 @@
 use crate::{
     color::{Color, BLACK},
     light::PointLight,
+    matrix::{Matrix4, MatrixError},
+    patterns::Pattern,
+    shape::Shape,
     tuple::{dot, normalize, reflect, Tuple},
     utils::eq_with_eps,
 };
+use std::rc::Rc;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Material {
     pub color: Color,
+    /// When set, the pattern is used instead of the flat color
+    pub pattern: Option<Rc<dyn Pattern>>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    /// How much of the surrounding scene is mirrored by the surface - 0 is completely matte,
+    /// 1 is a perfect mirror
+    pub reflective: f64,
+    /// How much light passes through the surface - 0 is opaque, 1 is completely transparent
+    pub transparency: f64,
+    /// How much light bends when entering the material - 1 for vacuum, 1.333 for water, 1.5 for
+    /// glass, 2.417 for diamond
+    pub refractive_index: f64,
 }
 
 impl Default for Material {
     fn default() -> Self {
         Material {
             color: Color::new(1.0, 1.0, 1.0),
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         }
     }
 }
 
 impl PartialEq for Material {
     fn eq(&self, other: &Material) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
         self.color == other.color
+            && same_pattern
             && eq_with_eps(self.ambient, other.ambient)
             && eq_with_eps(self.diffuse, other.diffuse)
             && eq_with_eps(self.specular, other.specular)
             && eq_with_eps(self.shininess, other.shininess)
+            && eq_with_eps(self.reflective, other.reflective)
+            && eq_with_eps(self.transparency, other.transparency)
+            && eq_with_eps(self.refractive_index, other.refractive_index)
     }
 }
 
 /// Calculating reflections using Phong reflection model
+/// Point in shadow is lit only by ambient light - diffuse and specular components depend on
+/// light source, which is blocked. Material is taken from the object; object and
+/// `parent_transform` (transformation of groups the object is nested in) are also needed to find
+/// out where on the object the pattern is sampled
 pub fn lighting(
-    m: &Material,
+    object: &dyn Shape,
+    parent_transform: Matrix4,
     light: PointLight,
     position: Tuple,
     eyev: Tuple,
     normalv: Tuple,
-) -> Color {
+    in_shadow: bool,
+) -> Result<Color, MatrixError> {
+    let m = object.get_material();
     let mut diffuse = BLACK;
     let mut specular = BLACK;
 
+    let color = match &m.pattern {
+        Some(pattern) => pattern.pattern_at_shape(object, parent_transform, position)?,
+        None => m.color,
+    };
+
     // combine the surface color with the light's color/intensity
-    let effective_color = m.color * light.intensity;
+    let effective_color = color * light.intensity;
 
     // find the direction to the light source
     let lightv = normalize(&(light.position - position));
 
     // compute the ambient contribution
     let ambient = effective_color * m.ambient;
+    if in_shadow {
+        return Ok(ambient);
+    }
 
     // light_dot_normal represents the consine of the angle between the light vector and the
     // normal vector. A negative number means the light is on the other side of the surface
@@ -75,13 +117,18 @@ pub fn lighting(
         }
     }
 
-    ambient + diffuse + specular
+    Ok(ambient + diffuse + specular)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tuple::{point, vector};
+    use crate::{
+        color::WHITE,
+        patterns::Stripe,
+        sphere::Sphere,
+        tuple::{point, vector},
+    };
 
     fn setup() -> (Material, Tuple) {
         let m = Material::default();
@@ -89,6 +136,30 @@ mod tests {
         (m, position)
     }
 
+    /// Lighting of a point on an untransformed sphere
+    fn sphere_lighting(
+        m: &Material,
+        light: PointLight,
+        position: Tuple,
+        eyev: Tuple,
+        normalv: Tuple,
+        in_shadow: bool,
+    ) -> Color {
+        let mut object = Sphere::default();
+        object.set_material(m.clone());
+        let parent_transform = Matrix4::identity_matrix();
+        lighting(
+            &object,
+            parent_transform,
+            light,
+            position,
+            eyev,
+            normalv,
+            in_shadow,
+        )
+        .unwrap()
+    }
+
     #[test]
     fn default_material() {
         let m = Material::default();
@@ -97,6 +168,9 @@ mod tests {
         assert!(eq_with_eps(0.9, m.diffuse));
         assert!(eq_with_eps(0.9, m.specular));
         assert!(eq_with_eps(200.0, m.shininess));
+        assert!(eq_with_eps(0.0, m.reflective));
+        assert!(eq_with_eps(0.0, m.transparency));
+        assert!(eq_with_eps(1.0, m.refractive_index));
     }
 
     #[test]
@@ -108,7 +182,7 @@ mod tests {
             position: point(0.0, 0.0, -10.0),
             intensity: Color::new(1.0, 1.0, 1.0),
         };
-        let result = lighting(&m, light, position, eyev, normalv);
+        let result = sphere_lighting(&m, light, position, eyev, normalv, false);
         assert_eq!(Color::new(1.9, 1.9, 1.9), result);
     }
 
@@ -122,7 +196,7 @@ mod tests {
             position: point(0.0, 0.0, -10.0),
             intensity: Color::new(1.0, 1.0, 1.0),
         };
-        let result = lighting(&m, light, position, eyev, normalv);
+        let result = sphere_lighting(&m, light, position, eyev, normalv, false);
         assert_eq!(Color::new(1.0, 1.0, 1.0), result);
     }
 
@@ -135,7 +209,7 @@ mod tests {
             position: point(0.0, 10.0, -10.0),
             intensity: Color::new(1.0, 1.0, 1.0),
         };
-        let result = lighting(&m, light, position, eyev, normalv);
+        let result = sphere_lighting(&m, light, position, eyev, normalv, false);
         assert_eq!(Color::new(0.7364, 0.7364, 0.7364), result);
     }
 
@@ -149,7 +223,7 @@ mod tests {
             position: point(0.0, 10.0, -10.0),
             intensity: Color::new(1.0, 1.0, 1.0),
         };
-        let result = lighting(&m, light, position, eyev, normalv);
+        let result = sphere_lighting(&m, light, position, eyev, normalv, false);
         assert_eq!(Color::new(1.6364, 1.6364, 1.6364), result);
     }
 
@@ -162,7 +236,42 @@ mod tests {
             position: point(0.0, 0.0, 10.0),
             intensity: Color::new(1.0, 1.0, 1.0),
         };
-        let result = lighting(&m, light, position, eyev, normalv);
+        let result = sphere_lighting(&m, light, position, eyev, normalv, false);
+        assert_eq!(Color::new(0.1, 0.1, 0.1), result);
+    }
+
+    #[test]
+    fn lighting_with_surface_in_shadow() {
+        let (m, position) = setup();
+        let eyev = vector(0.0, 0.0, -1.0);
+        let normalv = vector(0.0, 0.0, -1.0);
+        let light = PointLight {
+            position: point(0.0, 0.0, -10.0),
+            intensity: Color::new(1.0, 1.0, 1.0),
+        };
+        let in_shadow = true;
+        let result = sphere_lighting(&m, light, position, eyev, normalv, in_shadow);
         assert_eq!(Color::new(0.1, 0.1, 0.1), result);
     }
+
+    #[test]
+    fn lighting_with_pattern_applied() {
+        let m = Material {
+            pattern: Some(Rc::new(Stripe::new(WHITE, BLACK))),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Default::default()
+        };
+        let eyev = vector(0.0, 0.0, -1.0);
+        let normalv = vector(0.0, 0.0, -1.0);
+        let light = PointLight {
+            position: point(0.0, 0.0, -10.0),
+            intensity: Color::new(1.0, 1.0, 1.0),
+        };
+        let c1 = sphere_lighting(&m, light, point(0.9, 0.0, 0.0), eyev, normalv, false);
+        let c2 = sphere_lighting(&m, light, point(1.1, 0.0, 0.0), eyev, normalv, false);
+        assert_eq!(WHITE, c1);
+        assert_eq!(BLACK, c2);
+    }
 }
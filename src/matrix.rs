@@ -56,10 +56,10 @@ pub trait Matrix {
         match self.boundry_check(&row, &column) {
             Ok(_) => {
                 let d = self.submatrix(row, column)?.determiant()?;
-                if (row + column) % 2 == 0 {
+                if (row + column).is_multiple_of(2) {
                     Ok(d)
                 } else {
-                    Ok(d * -1.0)
+                    Ok(-d)
                 }
             }
             Err(e) => Err(e),
@@ -0,0 +1,209 @@
+use crate::tuple::{Tuple, TupleT};
+
+/// Ken Perlin's "improved" gradient noise. Lattice is shuffled from the seed, so the same seed
+/// always gives the same noise - renders stay reproducible between runs and machines
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    seed: u64,
+    // permutation of 0..256 repeated twice, so that hashing never has to wrap around
+    permutation: Vec<usize>,
+}
+
+/// SplitMix64 - tiny, fast and good enough to shuffle the permutation table
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Dot product of the distance vector with one of twelve gradient directions (edges of a cube),
+/// picked by the low bits of the hash
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Perlin {
+        let mut table: Vec<usize> = (0..256).collect();
+        let mut state = seed;
+        // Fisher-Yates shuffle
+        for i in (1..table.len()).rev() {
+            let j = (next_random(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+        let permutation = table.iter().chain(table.iter()).copied().collect();
+        Perlin { seed, permutation }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Noise value at a point, roughly in range [-1, 1]; it's 0 at every integer lattice point
+    /// and changes smoothly between them
+    pub fn noise(&self, x: f64, y: f64, z: f64) -> f64 {
+        let p = &self.permutation;
+        // unit cube containing the point
+        let xi = x.floor().rem_euclid(256.0) as usize;
+        let yi = y.floor().rem_euclid(256.0) as usize;
+        let zi = z.floor().rem_euclid(256.0) as usize;
+        // relative position of the point in the cube
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        // hashes of all eight cube corners
+        let a = p[xi] + yi;
+        let aa = p[a] + zi;
+        let ab = p[a + 1] + zi;
+        let b = p[xi + 1] + yi;
+        let ba = p[b] + zi;
+        let bb = p[b + 1] + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p[ab], x, y - 1.0, z),
+                    grad(p[bb], x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p[aa + 1], x, y, z - 1.0),
+                    grad(p[ba + 1], x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+
+    pub fn noise_at(&self, p: Tuple) -> f64 {
+        self.noise(p.get_x(), p.get_y(), p.get_z())
+    }
+
+    /// Fractal Brownian motion - sum of `octaves` layers of noise, each one with double the
+    /// frequency and half the amplitude of the previous one. The sum is normalized back to
+    /// roughly [-1, 1]
+    pub fn fbm(&self, p: Tuple, octaves: usize) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_value = 0.0;
+        for _ in 0..octaves {
+            total += self.noise_at(p * frequency) * amplitude;
+            max_value += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        if max_value > 0.0 {
+            total / max_value
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tuple::point, utils::eq_with_eps};
+
+    fn sample_points() -> Vec<Tuple> {
+        (0..50)
+            .map(|i| {
+                let i = i as f64;
+                point(i * 0.37 - 7.0, i * 0.71 + 0.3, 3.0 - i * 0.13)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn noise_is_zero_at_lattice_points() {
+        let perlin = Perlin::new(7);
+        for &(x, y, z) in [(0.0, 0.0, 0.0), (1.0, 2.0, 3.0), (-4.0, 7.0, -1.0)].iter() {
+            assert!(eq_with_eps(0.0, perlin.noise(x, y, z)));
+        }
+    }
+
+    #[test]
+    fn noise_stays_in_range() {
+        let perlin = Perlin::new(42);
+        for p in sample_points() {
+            let n = perlin.noise_at(p);
+            assert!((-1.0..=1.0).contains(&n));
+        }
+    }
+
+    #[test]
+    fn noise_is_deterministic_for_seed() {
+        let a = Perlin::new(1234);
+        let b = Perlin::new(1234);
+        let c = Perlin::new(4321);
+        let points = sample_points();
+        assert!(points.iter().all(|p| a.noise_at(*p) == b.noise_at(*p)));
+        assert!(points.iter().any(|p| a.noise_at(*p) != c.noise_at(*p)));
+    }
+
+    #[test]
+    fn noise_changes_smoothly() {
+        let perlin = Perlin::new(3);
+        let p = point(1.3, 2.7, -0.4);
+        let near = point(1.3001, 2.7, -0.4);
+        assert!((perlin.noise_at(p) - perlin.noise_at(near)).abs() < 0.001);
+    }
+
+    #[test]
+    fn fbm_with_single_octave_is_plain_noise() {
+        let perlin = Perlin::new(9);
+        for p in sample_points() {
+            assert!(eq_with_eps(perlin.noise_at(p), perlin.fbm(p, 1)));
+        }
+    }
+
+    #[test]
+    fn fbm_stays_in_range() {
+        let perlin = Perlin::new(9);
+        for p in sample_points() {
+            let n = perlin.fbm(p, 4);
+            assert!((-1.0..=1.0).contains(&n));
+        }
+        assert!(eq_with_eps(0.0, perlin.fbm(point(0.5, 0.5, 0.5), 0)));
+    }
+
+    #[test]
+    fn noise_values_do_not_change_between_versions() {
+        // golden values - reference images rendered with noise depend on them
+        let perlin = Perlin::new(2024);
+        assert!(eq_with_eps(-0.125, perlin.noise(0.5, 0.5, 0.5)));
+        assert!((-0.090005 - perlin.noise(1.25, -3.7, 2.1)).abs() < 0.000001);
+        assert!((0.019089 - perlin.fbm(point(0.3, 0.6, 0.9), 4)).abs() < 0.000001);
+    }
+}
@@ -0,0 +1,544 @@
+use crate::{
+    group::Group,
+    shape::Shape,
+    smooth_triangle::SmoothTriangle,
+    triangle::Triangle,
+    tuple::{point, vector, Tuple},
+};
+use std::{fs, rc::Rc};
+use thiserror::Error;
+
+/// Problems found in a single line of OBJ file; such lines are skipped and parsing continues
+#[derive(Error, Debug, PartialEq)]
+pub enum ObjError {
+    #[error("Line {line}: unsupported statement `{statement}` ignored")]
+    Unsupported { line: usize, statement: String },
+    #[error("Line {line}: malformed {statement} statement")]
+    Malformed { line: usize, statement: String },
+    #[error("Line {line}: index {index} does not refer to any {kind}")]
+    IndexOutOfRange {
+        line: usize,
+        index: i64,
+        kind: &'static str,
+    },
+}
+
+/// Named group of triangles from OBJ file
+pub struct ObjGroup {
+    pub name: String,
+    pub shapes: Vec<Rc<dyn Shape>>,
+}
+
+/// Result of parsing Wavefront OBJ file. Vertices and normals are indexed from 1, as in the file
+#[derive(Default)]
+pub struct ObjParser {
+    vertices: Vec<Tuple>,
+    normals: Vec<Tuple>,
+    /// Triangles which were defined before any named group
+    pub default_group: Vec<Rc<dyn Shape>>,
+    pub groups: Vec<ObjGroup>,
+    /// Index of the group faces are currently added to, None for the default group
+    current: Option<usize>,
+    /// Every line that was skipped, with the reason
+    pub ignored: Vec<ObjError>,
+}
+
+// Single vertex of a face - index of its position and optionally of its normal
+type FaceVertex = (usize, Option<usize>);
+
+impl ObjParser {
+    pub fn vertex(&self, index: usize) -> Option<Tuple> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.vertices.get(i))
+            .copied()
+    }
+
+    pub fn normal(&self, index: usize) -> Option<Tuple> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.normals.get(i))
+            .copied()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn normal_count(&self) -> usize {
+        self.normals.len()
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// All triangles from the file, ready to be pushed into World::objects
+    pub fn get_shapes(&self) -> Vec<Rc<dyn Shape>> {
+        self.default_group
+            .iter()
+            .chain(self.groups.iter().flat_map(|g| g.shapes.iter()))
+            .cloned()
+            .collect()
+    }
+
+    /// Converts the whole file into a single group; every named group becomes its subgroup
+    pub fn to_group(&self) -> Group {
+        let mut group = Group::default();
+        for shape in &self.default_group {
+            group.add_child(shape.clone());
+        }
+        for obj_group in &self.groups {
+            let mut subgroup = Group::default();
+            for shape in &obj_group.shapes {
+                subgroup.add_child(shape.clone());
+            }
+            group.add_child(Rc::new(subgroup));
+        }
+        group
+    }
+
+    fn current_group(&mut self) -> &mut Vec<Rc<dyn Shape>> {
+        match self.current {
+            Some(index) => &mut self.groups[index].shapes,
+            None => &mut self.default_group,
+        }
+    }
+
+    fn parse_line(&mut self, line_number: usize, line: &str) -> Result<(), ObjError> {
+        let mut tokens = line.split_whitespace();
+        let statement = match tokens.next() {
+            // blank lines and comments are not worth reporting
+            None => return Ok(()),
+            Some(s) if s.starts_with('#') => return Ok(()),
+            Some(s) => s,
+        };
+        let args: Vec<&str> = tokens.collect();
+        let malformed = || ObjError::Malformed {
+            line: line_number,
+            statement: statement.to_string(),
+        };
+
+        match statement {
+            "v" | "vn" => {
+                if args.len() < 3 {
+                    return Err(malformed());
+                }
+                let mut coords = [0.0; 3];
+                for (coord, arg) in coords.iter_mut().zip(args.iter()) {
+                    *coord = arg.parse().map_err(|_| malformed())?;
+                }
+                if statement == "v" {
+                    self.vertices.push(point(coords[0], coords[1], coords[2]));
+                } else {
+                    self.normals.push(vector(coords[0], coords[1], coords[2]));
+                }
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(malformed());
+                }
+                let face = args
+                    .iter()
+                    .map(|arg| self.parse_face_vertex(line_number, arg))
+                    .collect::<Result<Vec<FaceVertex>, ObjError>>()?;
+                let triangles = self.fan_triangulation(&face);
+                self.current_group().extend(triangles);
+            }
+            "g" => {
+                let name = args.join(" ");
+                if name.is_empty() {
+                    return Err(malformed());
+                }
+                // group named again later in the file gets the following faces, too
+                let index = match self.groups.iter().position(|g| g.name == name) {
+                    Some(index) => index,
+                    None => {
+                        self.groups.push(ObjGroup {
+                            name,
+                            shapes: vec![],
+                        });
+                        self.groups.len() - 1
+                    }
+                };
+                self.current = Some(index);
+            }
+            _ => {
+                return Err(ObjError::Unsupported {
+                    line: line_number,
+                    statement: statement.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Face vertex is given as `v`, `v/vt`, `v//vn` or `v/vt/vn`; texture coordinates are not
+    /// used. Negative indices are relative to the end of the list read so far
+    fn parse_face_vertex(&self, line_number: usize, arg: &str) -> Result<FaceVertex, ObjError> {
+        let malformed = || ObjError::Malformed {
+            line: line_number,
+            statement: "f".to_string(),
+        };
+        let mut parts = arg.split('/');
+        let vertex = parts
+            .next()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(malformed)?;
+        let vertex = Self::resolve_index(line_number, vertex, self.vertices.len(), "vertex")?;
+        let normal = match parts.nth(1) {
+            Some(n) if !n.is_empty() => Some(Self::resolve_index(
+                line_number,
+                n,
+                self.normals.len(),
+                "vertex normal",
+            )?),
+            _ => None,
+        };
+        Ok((vertex, normal))
+    }
+
+    fn resolve_index(
+        line_number: usize,
+        index: &str,
+        count: usize,
+        kind: &'static str,
+    ) -> Result<usize, ObjError> {
+        let index: i64 = index.parse().map_err(|_| ObjError::Malformed {
+            line: line_number,
+            statement: "f".to_string(),
+        })?;
+        let resolved = if index < 0 {
+            count as i64 + 1 + index
+        } else {
+            index
+        };
+        if resolved < 1 || resolved > count as i64 {
+            Err(ObjError::IndexOutOfRange {
+                line: line_number,
+                index,
+                kind,
+            })
+        } else {
+            Ok(resolved as usize)
+        }
+    }
+
+    /// Splits convex polygon into triangles which share the first vertex
+    fn fan_triangulation(&self, face: &[FaceVertex]) -> Vec<Rc<dyn Shape>> {
+        let mut triangles: Vec<Rc<dyn Shape>> = vec![];
+        for i in 1..face.len() - 1 {
+            let (v1, n1) = face[0];
+            let (v2, n2) = face[i];
+            let (v3, n3) = face[i + 1];
+            let p1 = self.vertices[v1 - 1];
+            let p2 = self.vertices[v2 - 1];
+            let p3 = self.vertices[v3 - 1];
+            match (n1, n2, n3) {
+                (Some(n1), Some(n2), Some(n3)) => triangles.push(Rc::new(SmoothTriangle::new(
+                    p1,
+                    p2,
+                    p3,
+                    self.normals[n1 - 1],
+                    self.normals[n2 - 1],
+                    self.normals[n3 - 1],
+                ))),
+                _ => triangles.push(Rc::new(Triangle::new(p1, p2, p3))),
+            }
+        }
+        triangles
+    }
+}
+
+/// Parses content of Wavefront OBJ file. Lines which can't be understood don't stop parsing -
+/// they are collected in `ignored`
+pub fn parse_obj(content: &str) -> ObjParser {
+    let mut parser = ObjParser::default();
+    for (i, line) in content.lines().enumerate() {
+        if let Err(e) = parser.parse_line(i + 1, line) {
+            parser.ignored.push(e);
+        }
+    }
+    parser
+}
+
+pub fn load_obj(path: &str) -> std::io::Result<ObjParser> {
+    Ok(parse_obj(&fs::read_to_string(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::ShapeType;
+
+    fn triangle(shape: &Rc<dyn Shape>) -> Triangle {
+        shape.as_any().downcast_ref::<Triangle>().unwrap().clone()
+    }
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright\n\
+                         who traveled much faster than light.\n\
+                         She set out one day\n\
+                         in a relative way,\n\
+                         and came back the previous night.";
+        let parser = parse_obj(gibberish);
+        assert_eq!(5, parser.ignored.len());
+        assert_eq!(
+            ObjError::Unsupported {
+                line: 1,
+                statement: "There".to_string()
+            },
+            parser.ignored[0]
+        );
+        assert_eq!(
+            ObjError::Unsupported {
+                line: 5,
+                statement: "and".to_string()
+            },
+            parser.ignored[4]
+        );
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_not_reported() {
+        let parser = parse_obj("# comment\n\n   \nv 1 2 3\n");
+        assert!(parser.ignored.is_empty());
+        assert_eq!(1, parser.vertex_count());
+    }
+
+    #[test]
+    fn vertex_records() {
+        let file = "v -1 1 0\n\
+                    v -1.0000 0.5000 0.0000\n\
+                    v 1 0 0\n\
+                    v 1 1 0";
+        let parser = parse_obj(file);
+        assert_eq!(Some(point(-1.0, 1.0, 0.0)), parser.vertex(1));
+        assert_eq!(Some(point(-1.0, 0.5, 0.0)), parser.vertex(2));
+        assert_eq!(Some(point(1.0, 0.0, 0.0)), parser.vertex(3));
+        assert_eq!(Some(point(1.0, 1.0, 0.0)), parser.vertex(4));
+        assert_eq!(None, parser.vertex(0));
+        assert_eq!(None, parser.vertex(5));
+    }
+
+    #[test]
+    fn parsing_triangle_faces() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    v 1 1 0\n\
+                    \n\
+                    f 1 2 3\n\
+                    f 1 3 4";
+        let parser = parse_obj(file);
+        assert!(parser.ignored.is_empty());
+        let t1 = triangle(&parser.default_group[0]);
+        let t2 = triangle(&parser.default_group[1]);
+        assert_eq!(parser.vertex(1).unwrap(), t1.get_p1());
+        assert_eq!(parser.vertex(2).unwrap(), t1.get_p2());
+        assert_eq!(parser.vertex(3).unwrap(), t1.get_p3());
+        assert_eq!(parser.vertex(1).unwrap(), t2.get_p1());
+        assert_eq!(parser.vertex(3).unwrap(), t2.get_p2());
+        assert_eq!(parser.vertex(4).unwrap(), t2.get_p3());
+    }
+
+    #[test]
+    fn triangulating_polygons() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    v 1 1 0\n\
+                    v 0 2 0\n\
+                    \n\
+                    f 1 2 3 4 5";
+        let parser = parse_obj(file);
+        assert_eq!(3, parser.default_group.len());
+        let t1 = triangle(&parser.default_group[0]);
+        let t2 = triangle(&parser.default_group[1]);
+        let t3 = triangle(&parser.default_group[2]);
+        assert_eq!(parser.vertex(1).unwrap(), t1.get_p1());
+        assert_eq!(parser.vertex(2).unwrap(), t1.get_p2());
+        assert_eq!(parser.vertex(3).unwrap(), t1.get_p3());
+        assert_eq!(parser.vertex(1).unwrap(), t2.get_p1());
+        assert_eq!(parser.vertex(3).unwrap(), t2.get_p2());
+        assert_eq!(parser.vertex(4).unwrap(), t2.get_p3());
+        assert_eq!(parser.vertex(1).unwrap(), t3.get_p1());
+        assert_eq!(parser.vertex(4).unwrap(), t3.get_p2());
+        assert_eq!(parser.vertex(5).unwrap(), t3.get_p3());
+    }
+
+    #[test]
+    fn triangles_in_groups() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    v 1 1 0\n\
+                    g FirstGroup\n\
+                    f 1 2 3\n\
+                    g SecondGroup\n\
+                    f 1 3 4";
+        let parser = parse_obj(file);
+        assert!(parser.default_group.is_empty());
+        let g1 = parser.group("FirstGroup").unwrap();
+        let g2 = parser.group("SecondGroup").unwrap();
+        let t1 = triangle(&g1.shapes[0]);
+        let t2 = triangle(&g2.shapes[0]);
+        assert_eq!(parser.vertex(1).unwrap(), t1.get_p1());
+        assert_eq!(parser.vertex(2).unwrap(), t1.get_p2());
+        assert_eq!(parser.vertex(3).unwrap(), t1.get_p3());
+        assert_eq!(parser.vertex(1).unwrap(), t2.get_p1());
+        assert_eq!(parser.vertex(3).unwrap(), t2.get_p2());
+        assert_eq!(parser.vertex(4).unwrap(), t2.get_p3());
+        assert_eq!(2, parser.get_shapes().len());
+    }
+
+    #[test]
+    fn reopening_group_adds_faces_to_it() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    v 1 1 0\n\
+                    g FirstGroup\n\
+                    f 1 2 3\n\
+                    g SecondGroup\n\
+                    f 1 3 4\n\
+                    g FirstGroup\n\
+                    f 2 3 4";
+        let parser = parse_obj(file);
+        assert_eq!(2, parser.groups.len());
+        let g1 = parser.group("FirstGroup").unwrap();
+        assert_eq!(2, g1.shapes.len());
+        assert_eq!(parser.vertex(2).unwrap(), triangle(&g1.shapes[1]).get_p1());
+        assert_eq!(1, parser.group("SecondGroup").unwrap().shapes.len());
+    }
+
+    #[test]
+    fn converting_obj_file_to_group() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    v 1 1 0\n\
+                    f 1 2 3\n\
+                    g FirstGroup\n\
+                    f 1 2 3\n\
+                    g SecondGroup\n\
+                    f 1 3 4";
+        let parser = parse_obj(file);
+        let g = parser.to_group();
+        assert_eq!(3, g.get_children().len());
+        assert_eq!(ShapeType::Triangle, g.get_children()[0].get_shape_type());
+        assert_eq!(ShapeType::Group, g.get_children()[1].get_shape_type());
+        assert_eq!(ShapeType::Group, g.get_children()[2].get_shape_type());
+        let first = g.get_children()[1]
+            .as_any()
+            .downcast_ref::<Group>()
+            .unwrap();
+        assert_eq!(
+            parser.group("FirstGroup").unwrap().shapes[0].get_id(),
+            first.get_children()[0].get_id()
+        );
+    }
+
+    #[test]
+    fn vertex_normal_records() {
+        let file = "vn 0 0 1\n\
+                    vn 0.707 0 -0.707\n\
+                    vn 1 2 3";
+        let parser = parse_obj(file);
+        assert_eq!(Some(vector(0.0, 0.0, 1.0)), parser.normal(1));
+        assert_eq!(Some(vector(0.707, 0.0, -0.707)), parser.normal(2));
+        assert_eq!(Some(vector(1.0, 2.0, 3.0)), parser.normal(3));
+    }
+
+    #[test]
+    fn faces_with_normals() {
+        let file = "v 0 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    \n\
+                    vn -1 0 0\n\
+                    vn 1 0 0\n\
+                    vn 0 1 0\n\
+                    \n\
+                    f 1//3 2//1 3//2\n\
+                    f 1/0/3 2/102/1 3/14/2";
+        let parser = parse_obj(file);
+        assert_eq!(2, parser.default_group.len());
+        for shape in &parser.default_group {
+            assert_eq!(ShapeType::SmoothTriangle, shape.get_shape_type());
+            let t = shape.as_any().downcast_ref::<SmoothTriangle>().unwrap();
+            assert_eq!(parser.vertex(1).unwrap(), t.get_p1());
+            assert_eq!(parser.vertex(2).unwrap(), t.get_p2());
+            assert_eq!(parser.vertex(3).unwrap(), t.get_p3());
+            assert_eq!(parser.normal(3).unwrap(), t.get_n1());
+            assert_eq!(parser.normal(1).unwrap(), t.get_n2());
+            assert_eq!(parser.normal(2).unwrap(), t.get_n3());
+        }
+    }
+
+    #[test]
+    fn faces_with_negative_indices() {
+        let file = "v -1 1 0\n\
+                    v -1 0 0\n\
+                    v 1 0 0\n\
+                    f -3 -2 -1";
+        let parser = parse_obj(file);
+        let t = triangle(&parser.default_group[0]);
+        assert_eq!(parser.vertex(1).unwrap(), t.get_p1());
+        assert_eq!(parser.vertex(3).unwrap(), t.get_p3());
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_line_numbers() {
+        let file = "v 1 2\n\
+                    v 1 two 3\n\
+                    v 1 0 0\n\
+                    f 1 2\n\
+                    f 1 1 7\n\
+                    f 1 x 1\n\
+                    vt 0.5 0.5\n\
+                    g";
+        let parser = parse_obj(file);
+        assert!(parser.default_group.is_empty());
+        assert_eq!(1, parser.vertex_count());
+        assert_eq!(
+            vec![
+                ObjError::Malformed {
+                    line: 1,
+                    statement: "v".to_string()
+                },
+                ObjError::Malformed {
+                    line: 2,
+                    statement: "v".to_string()
+                },
+                ObjError::Malformed {
+                    line: 4,
+                    statement: "f".to_string()
+                },
+                ObjError::IndexOutOfRange {
+                    line: 5,
+                    index: 7,
+                    kind: "vertex"
+                },
+                ObjError::Malformed {
+                    line: 6,
+                    statement: "f".to_string()
+                },
+                ObjError::Unsupported {
+                    line: 7,
+                    statement: "vt".to_string()
+                },
+                ObjError::Malformed {
+                    line: 8,
+                    statement: "g".to_string()
+                },
+            ],
+            parser.ignored
+        );
+        assert_eq!(
+            "Line 5: index 7 does not refer to any vertex",
+            parser.ignored[3].to_string()
+        );
+    }
+}
@@ -0,0 +1,16 @@
+pub use self::{
+    blend::*, checker::*, gradient::*, pattern::*, perturbed::*, radial_gradient::*, ring::*,
+    solid::*, stripe::*, texture::*, uv_mapping::*,
+};
+
+mod blend;
+mod checker;
+mod gradient;
+mod pattern;
+mod perturbed;
+mod radial_gradient;
+mod ring;
+mod solid;
+mod stripe;
+mod texture;
+mod uv_mapping;
@@ -0,0 +1,100 @@
+use crate::{
+    color::Color,
+    matrix::{Matrix4, MatrixError},
+    patterns::Pattern,
+    tuple::Tuple,
+};
+use std::rc::Rc;
+
+/// Two patterns mixed together - every point gets the weighted average of both patterns' colors
+#[derive(Debug)]
+pub struct Blend {
+    transform: Matrix4,
+    a: Rc<dyn Pattern>,
+    b: Rc<dyn Pattern>,
+    ratio: f64,
+}
+
+impl Blend {
+    /// Both patterns contribute equally
+    pub fn new(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Blend {
+        Self::new_with_ratio(a, b, 0.5)
+    }
+
+    /// `ratio` is the share of the second pattern - 0 gives only the first one, 1 only the second
+    pub fn new_with_ratio(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>, ratio: f64) -> Blend {
+        Blend {
+            transform: Matrix4::identity_matrix(),
+            a,
+            b,
+            ratio,
+        }
+    }
+
+    pub fn get_ratio(&self) -> f64 {
+        self.ratio
+    }
+}
+
+impl Pattern for Blend {
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn pattern_at(&self, p: Tuple) -> Result<Color, MatrixError> {
+        let a = self.a.nested_pattern_at(p)?;
+        let b = self.b.nested_pattern_at(p)?;
+        Ok(a * (1.0 - self.ratio) + b * self.ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{BLACK, WHITE},
+        patterns::{Solid, Stripe},
+        transformations::rotation_y,
+        tuple::point,
+        utils::PI,
+    };
+
+    #[test]
+    fn blend_averages_colors_of_both_patterns() {
+        let pattern = Blend::new(
+            Rc::new(Solid::new(Color::new(1.0, 0.0, 0.0))),
+            Rc::new(Solid::new(Color::new(0.0, 0.0, 1.0))),
+        );
+        let c = pattern.pattern_at(point(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(Color::new(0.5, 0.0, 0.5), c);
+    }
+
+    #[test]
+    fn blend_with_ratio_favours_one_pattern() {
+        let pattern =
+            Blend::new_with_ratio(Rc::new(Solid::new(WHITE)), Rc::new(Solid::new(BLACK)), 0.25);
+        let c = pattern.pattern_at(point(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(Color::new(0.75, 0.75, 0.75), c);
+    }
+
+    #[test]
+    fn blending_crossed_stripes() {
+        // stripes along x blended with the same stripes rotated to run along z
+        let mut crossing = Stripe::new(WHITE, BLACK);
+        crossing.set_transform(rotation_y(PI / 2.0));
+        let pattern = Blend::new(Rc::new(Stripe::new(WHITE, BLACK)), Rc::new(crossing));
+        let cases = [
+            (point(0.5, 0.0, -0.5), WHITE),
+            (point(1.5, 0.0, -0.5), Color::new(0.5, 0.5, 0.5)),
+            (point(0.5, 0.0, 0.5), Color::new(0.5, 0.5, 0.5)),
+            (point(1.5, 0.0, 0.5), BLACK),
+        ];
+        for (p, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(*p).unwrap());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{patterns::two_patterns, tuple::TupleT};
+
+two_patterns!(
+    /// Three dimensional checker board - alternating unit cubes of two colors
+    Checker,
+    |self, p| {
+        let sum = p.get_x().floor() + p.get_y().floor() + p.get_z().floor();
+        if sum.rem_euclid(2.0) == 0.0 {
+            self.a.nested_pattern_at(p)
+        } else {
+            self.b.nested_pattern_at(p)
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Color, BLACK, WHITE},
+        patterns::{Pattern, Solid, Stripe},
+        transformations::scaling,
+        tuple::point,
+    };
+    use std::rc::Rc;
+
+    #[test]
+    fn checkers_repeat_in_every_axis() {
+        let pattern = Checker::new(WHITE, BLACK);
+        let cases = [
+            (point(0.0, 0.0, 0.0), WHITE),
+            (point(0.99, 0.0, 0.0), WHITE),
+            (point(1.01, 0.0, 0.0), BLACK),
+            (point(0.0, 0.99, 0.0), WHITE),
+            (point(0.0, 1.01, 0.0), BLACK),
+            (point(0.0, 0.0, 0.99), WHITE),
+            (point(0.0, 0.0, 1.01), BLACK),
+        ];
+        for (p, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(*p).unwrap());
+        }
+    }
+
+    #[test]
+    fn checker_of_stripes_keeps_transformations_of_sub_patterns() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let mut stripes = Stripe::new(red, WHITE);
+        stripes.set_transform(scaling(0.25, 0.25, 0.25));
+        let mut pattern = Checker::new_nested(Rc::new(stripes), Rc::new(Solid::new(BLACK)));
+        pattern.set_transform(scaling(2.0, 2.0, 2.0));
+        let cases = [
+            // first cell of the checker holds stripes half a unit wide
+            (point(0.1, 0.0, 0.0), red),
+            (point(0.6, 0.0, 0.0), WHITE),
+            (point(1.1, 0.0, 0.0), red),
+            (point(2.5, 0.0, 0.0), BLACK),
+            (point(3.9, 0.0, 0.0), BLACK),
+        ];
+        for (p, color) in cases.iter() {
+            let c = pattern.nested_pattern_at(*p).unwrap();
+            assert_eq!(*color, c);
+        }
+    }
+}
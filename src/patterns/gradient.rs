@@ -0,0 +1,36 @@
+use crate::{patterns::two_patterns, tuple::TupleT};
+
+two_patterns!(
+    /// Linear blend from the first color to the second one along the x axis, repeated every unit
+    Gradient,
+    |self, p| {
+        let a = self.a.nested_pattern_at(p)?;
+        let b = self.b.nested_pattern_at(p)?;
+        let fraction = p.get_x() - p.get_x().floor();
+        Ok(a + (b - a) * fraction)
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Color, BLACK, WHITE},
+        patterns::Pattern,
+        tuple::point,
+    };
+
+    #[test]
+    fn gradient_linearly_interpolates_between_colors() {
+        let pattern = Gradient::new(WHITE, BLACK);
+        let cases = [
+            (0.0, WHITE),
+            (0.25, Color::new(0.75, 0.75, 0.75)),
+            (0.5, Color::new(0.5, 0.5, 0.5)),
+            (0.75, Color::new(0.25, 0.25, 0.25)),
+        ];
+        for (x, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(point(*x, 0.0, 0.0)).unwrap());
+        }
+    }
+}
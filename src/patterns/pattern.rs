@@ -0,0 +1,191 @@
+use crate::{
+    color::Color,
+    matrix::{Matrix4, MatrixError},
+    shape::Shape,
+    tuple::Tuple,
+};
+use std::fmt;
+
+/// Color varying over the surface of an object. Pattern has its own transformation, applied on
+/// top of the object's one - the pattern can be scaled or rotated independently of the object
+pub trait Pattern: fmt::Debug {
+    fn get_transform(&self) -> Matrix4;
+    fn set_transform(&mut self, transform: Matrix4);
+    /// Color at a point given in pattern space
+    fn pattern_at(&self, pattern_point: Tuple) -> Result<Color, MatrixError>;
+
+    /// Color at a point given in space of the pattern this one is nested in - the point is
+    /// converted with this pattern's own transformation first
+    fn nested_pattern_at(&self, parent_point: Tuple) -> Result<Color, MatrixError> {
+        self.pattern_at(self.get_transform().inverse()? * parent_point)
+    }
+
+    /// Color at a point given in world space on given object. `parent_transform` is combined
+    /// transformation of groups the object is nested in (see `Intersection::parent_transform`)
+    fn pattern_at_shape(
+        &self,
+        object: &dyn Shape,
+        parent_transform: Matrix4,
+        world_point: Tuple,
+    ) -> Result<Color, MatrixError> {
+        let object_point = (parent_transform * object.get_transform()).inverse()? * world_point;
+        self.nested_pattern_at(object_point)
+    }
+}
+
+/// Declares pattern which picks or mixes colors of two sub-patterns, `a` and `b`, at every point;
+/// only `pattern_at` is written by hand. Sub-patterns are given as plain colors (`new`) or as
+/// patterns (`new_nested`) - nested sub-patterns keep their own transformations, applied on top
+/// of the declared pattern's one
+macro_rules! two_patterns {
+    ($(#[$attr:meta])* $name:ident, |$self:ident, $p:ident| $pattern_at:block) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name {
+            transform: $crate::matrix::Matrix4,
+            a: std::rc::Rc<dyn $crate::patterns::Pattern>,
+            b: std::rc::Rc<dyn $crate::patterns::Pattern>,
+        }
+
+        impl $name {
+            pub fn new(a: $crate::color::Color, b: $crate::color::Color) -> $name {
+                Self::new_nested(
+                    std::rc::Rc::new($crate::patterns::Solid::new(a)),
+                    std::rc::Rc::new($crate::patterns::Solid::new(b)),
+                )
+            }
+
+            pub fn new_nested(
+                a: std::rc::Rc<dyn $crate::patterns::Pattern>,
+                b: std::rc::Rc<dyn $crate::patterns::Pattern>,
+            ) -> $name {
+                $name {
+                    transform: $crate::matrix::Matrix4::identity_matrix(),
+                    a,
+                    b,
+                }
+            }
+
+            pub fn get_a(&self) -> &std::rc::Rc<dyn $crate::patterns::Pattern> {
+                &self.a
+            }
+
+            pub fn get_b(&self) -> &std::rc::Rc<dyn $crate::patterns::Pattern> {
+                &self.b
+            }
+        }
+
+        impl $crate::patterns::Pattern for $name {
+            fn get_transform(&self) -> $crate::matrix::Matrix4 {
+                self.transform
+            }
+
+            fn set_transform(&mut self, transform: $crate::matrix::Matrix4) {
+                self.transform = transform;
+            }
+
+            fn pattern_at(
+                &$self,
+                $p: $crate::tuple::Tuple,
+            ) -> Result<$crate::color::Color, $crate::matrix::MatrixError> $pattern_at
+        }
+    };
+}
+pub(crate) use two_patterns;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        sphere::Sphere,
+        transformations::{scaling, translation},
+        tuple::{point, TupleT},
+    };
+
+    /// Pattern which returns the point it was sampled at as a color, so the conversion between
+    /// spaces can be tested
+    #[derive(Debug)]
+    pub(crate) struct TestPattern {
+        transform: Matrix4,
+    }
+
+    impl Default for TestPattern {
+        fn default() -> Self {
+            TestPattern {
+                transform: Matrix4::identity_matrix(),
+            }
+        }
+    }
+
+    impl Pattern for TestPattern {
+        fn get_transform(&self) -> Matrix4 {
+            self.transform
+        }
+
+        fn set_transform(&mut self, transform: Matrix4) {
+            self.transform = transform;
+        }
+
+        fn pattern_at(&self, p: Tuple) -> Result<Color, MatrixError> {
+            Ok(Color::new(p.get_x(), p.get_y(), p.get_z()))
+        }
+    }
+
+    #[test]
+    fn default_pattern_transformation() {
+        let pattern = TestPattern::default();
+        assert_eq!(Matrix4::identity_matrix(), pattern.get_transform());
+    }
+
+    #[test]
+    fn assigning_transformation() {
+        let mut pattern = TestPattern::default();
+        pattern.set_transform(translation(1.0, 2.0, 3.0));
+        assert_eq!(translation(1.0, 2.0, 3.0), pattern.get_transform());
+    }
+
+    #[test]
+    fn pattern_with_object_transformation() {
+        let mut shape = Sphere::default();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let pattern = TestPattern::default();
+        let c = pattern
+            .pattern_at_shape(&shape, Matrix4::identity_matrix(), point(2.0, 3.0, 4.0))
+            .unwrap();
+        assert_eq!(Color::new(1.0, 1.5, 2.0), c);
+    }
+
+    #[test]
+    fn pattern_with_pattern_transformation() {
+        let shape = Sphere::default();
+        let mut pattern = TestPattern::default();
+        pattern.set_transform(scaling(2.0, 2.0, 2.0));
+        let c = pattern
+            .pattern_at_shape(&shape, Matrix4::identity_matrix(), point(2.0, 3.0, 4.0))
+            .unwrap();
+        assert_eq!(Color::new(1.0, 1.5, 2.0), c);
+    }
+
+    #[test]
+    fn pattern_with_both_object_and_pattern_transformation() {
+        let mut shape = Sphere::default();
+        shape.set_transform(scaling(2.0, 2.0, 2.0));
+        let mut pattern = TestPattern::default();
+        pattern.set_transform(translation(0.5, 1.0, 1.5));
+        let c = pattern
+            .pattern_at_shape(&shape, Matrix4::identity_matrix(), point(2.5, 3.0, 3.5))
+            .unwrap();
+        assert_eq!(Color::new(0.75, 0.5, 0.25), c);
+    }
+
+    #[test]
+    fn pattern_on_object_nested_in_group() {
+        let mut shape = Sphere::default();
+        shape.set_transform(translation(5.0, 0.0, 0.0));
+        let pattern = TestPattern::default();
+        let c = pattern
+            .pattern_at_shape(&shape, scaling(2.0, 2.0, 2.0), point(12.0, 2.0, 4.0))
+            .unwrap();
+        assert_eq!(Color::new(1.0, 1.0, 2.0), c);
+    }
+}
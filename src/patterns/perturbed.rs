@@ -0,0 +1,115 @@
+use crate::{
+    color::Color,
+    matrix::{Matrix4, MatrixError},
+    noise::Perlin,
+    patterns::Pattern,
+    tuple::{vector, Tuple},
+};
+use std::rc::Rc;
+
+/// Wraps another pattern and jitters every sample point with Perlin noise before passing it on,
+/// so that straight edges of the inner pattern become wavy. Same seed gives the same image
+#[derive(Debug)]
+pub struct Perturbed {
+    transform: Matrix4,
+    pattern: Rc<dyn Pattern>,
+    noise: Perlin,
+    scale: f64,
+    octaves: usize,
+}
+
+impl Perturbed {
+    /// `scale` is the largest distance a point can be moved by, `octaves` is the number of
+    /// noise layers (see `Perlin::fbm`)
+    pub fn new(pattern: Rc<dyn Pattern>, scale: f64, octaves: usize, seed: u64) -> Perturbed {
+        Perturbed {
+            transform: Matrix4::identity_matrix(),
+            pattern,
+            noise: Perlin::new(seed),
+            scale,
+            octaves,
+        }
+    }
+
+    pub fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn get_octaves(&self) -> usize {
+        self.octaves
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.noise.get_seed()
+    }
+}
+
+impl Pattern for Perturbed {
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn pattern_at(&self, p: Tuple) -> Result<Color, MatrixError> {
+        // every axis is displaced by noise sampled at a different, distant spot - otherwise the
+        // point would only ever move along the (1, 1, 1) diagonal
+        let offset = vector(
+            self.noise.fbm(p, self.octaves),
+            self.noise.fbm(p + vector(31.4, 15.9, 26.5), self.octaves),
+            self.noise.fbm(p + vector(-27.1, 82.8, -18.2), self.octaves),
+        );
+        self.pattern.nested_pattern_at(p + offset * self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{BLACK, WHITE},
+        patterns::Stripe,
+        tuple::point,
+    };
+
+    fn sample_points() -> Vec<Tuple> {
+        (0..40)
+            .map(|i| point(i as f64 * 0.1 - 2.0, 0.3, i as f64 * 0.07))
+            .collect()
+    }
+
+    #[test]
+    fn perturbed_pattern_without_scale_is_inner_pattern() {
+        let stripes: Rc<dyn Pattern> = Rc::new(Stripe::new(WHITE, BLACK));
+        let pattern = Perturbed::new(stripes.clone(), 0.0, 3, 1);
+        for p in sample_points() {
+            assert_eq!(
+                stripes.pattern_at(p).unwrap(),
+                pattern.pattern_at(p).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn perturbed_pattern_moves_edges_of_inner_pattern() {
+        let stripes: Rc<dyn Pattern> = Rc::new(Stripe::new(WHITE, BLACK));
+        let pattern = Perturbed::new(stripes.clone(), 0.5, 2, 1);
+        let differs = sample_points()
+            .iter()
+            .any(|p| stripes.pattern_at(*p).unwrap() != pattern.pattern_at(*p).unwrap());
+        assert!(differs);
+    }
+
+    #[test]
+    fn perturbed_pattern_is_deterministic_for_seed() {
+        let stripes: Rc<dyn Pattern> = Rc::new(Stripe::new(WHITE, BLACK));
+        let a = Perturbed::new(stripes.clone(), 0.5, 2, 99);
+        let b = Perturbed::new(stripes, 0.5, 2, 99);
+        assert_eq!(99, a.get_seed());
+        for p in sample_points() {
+            assert_eq!(a.pattern_at(p).unwrap(), b.pattern_at(p).unwrap());
+        }
+    }
+}
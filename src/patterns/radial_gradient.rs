@@ -0,0 +1,39 @@
+use crate::{patterns::two_patterns, tuple::TupleT};
+
+two_patterns!(
+    /// Gradient spreading out from the y axis - like rings, but blending smoothly from the first
+    /// color to the second one within every unit of distance
+    RadialGradient,
+    |self, p| {
+        let a = self.a.nested_pattern_at(p)?;
+        let b = self.b.nested_pattern_at(p)?;
+        let distance = (p.get_x().powi(2) + p.get_z().powi(2)).sqrt();
+        let fraction = distance - distance.floor();
+        Ok(a + (b - a) * fraction)
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{Color, BLACK, WHITE},
+        patterns::Pattern,
+        tuple::point,
+    };
+
+    #[test]
+    fn radial_gradient_interpolates_with_distance_from_y_axis() {
+        let pattern = RadialGradient::new(WHITE, BLACK);
+        let cases = [
+            (point(0.0, 0.0, 0.0), WHITE),
+            (point(0.25, 0.0, 0.0), Color::new(0.75, 0.75, 0.75)),
+            (point(0.0, 5.0, 0.5), Color::new(0.5, 0.5, 0.5)),
+            (point(0.6, 0.0, 0.8), WHITE),
+            (point(-0.45, 0.0, -0.6), Color::new(0.25, 0.25, 0.25)),
+        ];
+        for (p, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(*p).unwrap());
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{patterns::two_patterns, tuple::TupleT};
+
+two_patterns!(
+    /// Concentric rings of two colors around the y axis, each one unit wide
+    Ring,
+    |self, p| {
+        let distance = (p.get_x().powi(2) + p.get_z().powi(2)).sqrt();
+        if distance.floor().rem_euclid(2.0) == 0.0 {
+            self.a.nested_pattern_at(p)
+        } else {
+            self.b.nested_pattern_at(p)
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{BLACK, WHITE},
+        patterns::Pattern,
+        tuple::point,
+    };
+
+    #[test]
+    fn ring_extends_in_both_x_and_z() {
+        let pattern = Ring::new(WHITE, BLACK);
+        let cases = [
+            (point(0.0, 0.0, 0.0), WHITE),
+            (point(1.0, 0.0, 0.0), BLACK),
+            (point(0.0, 0.0, 1.0), BLACK),
+            // 0.708 = just slightly more than sqrt(2)/2
+            (point(0.708, 0.0, 0.708), BLACK),
+        ];
+        for (p, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(*p).unwrap());
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    color::Color,
+    matrix::{Matrix4, MatrixError},
+    patterns::Pattern,
+    tuple::Tuple,
+};
+
+/// Single color everywhere - lets plain colors be used wherever other patterns nest a pattern
+#[derive(Debug)]
+pub struct Solid {
+    transform: Matrix4,
+    color: Color,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Solid {
+        Solid {
+            transform: Matrix4::identity_matrix(),
+            color,
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn pattern_at(&self, _p: Tuple) -> Result<Color, MatrixError> {
+        Ok(self.color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::point;
+
+    #[test]
+    fn solid_pattern_is_same_everywhere() {
+        let pattern = Solid::new(Color::new(0.2, 0.4, 0.6));
+        for p in [point(0.0, 0.0, 0.0), point(-3.5, 7.1, 12.0)].iter() {
+            assert_eq!(Color::new(0.2, 0.4, 0.6), pattern.pattern_at(*p).unwrap());
+        }
+    }
+}
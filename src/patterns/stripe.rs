@@ -0,0 +1,61 @@
+use crate::{patterns::two_patterns, tuple::TupleT};
+
+two_patterns!(
+    /// Alternating stripes of two colors, each one unit wide, perpendicular to the x axis
+    Stripe,
+    |self, p| {
+        if p.get_x().floor().rem_euclid(2.0) == 0.0 {
+            self.a.nested_pattern_at(p)
+        } else {
+            self.b.nested_pattern_at(p)
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        color::{BLACK, WHITE},
+        patterns::Pattern,
+        tuple::point,
+    };
+
+    #[test]
+    fn creating_stripe_pattern() {
+        let pattern = Stripe::new(WHITE, BLACK);
+        let origin = point(0.0, 0.0, 0.0);
+        assert_eq!(WHITE, pattern.get_a().pattern_at(origin).unwrap());
+        assert_eq!(BLACK, pattern.get_b().pattern_at(origin).unwrap());
+    }
+
+    #[test]
+    fn stripe_pattern_is_constant_in_y_and_z() {
+        let pattern = Stripe::new(WHITE, BLACK);
+        for p in [
+            point(0.0, 1.0, 0.0),
+            point(0.0, 2.0, 0.0),
+            point(0.0, 0.0, 2.0),
+        ]
+        .iter()
+        {
+            assert_eq!(WHITE, pattern.pattern_at(*p).unwrap());
+        }
+    }
+
+    #[test]
+    fn stripe_pattern_alternates_in_x() {
+        let pattern = Stripe::new(WHITE, BLACK);
+        let cases = [
+            (0.0, WHITE),
+            (0.9, WHITE),
+            (1.0, BLACK),
+            (-0.1, BLACK),
+            (-1.0, BLACK),
+            (-1.1, WHITE),
+        ];
+        for (x, color) in cases.iter() {
+            assert_eq!(*color, pattern.pattern_at(point(*x, 0.0, 0.0)).unwrap());
+        }
+    }
+}
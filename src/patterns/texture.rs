@@ -0,0 +1,164 @@
+use crate::{
+    canvas::Canvas,
+    color::{Color, BLACK},
+    matrix::{Matrix4, MatrixError},
+    patterns::{Pattern, UvMapping},
+    tuple::Tuple,
+};
+use std::rc::Rc;
+
+/// How colors are read from the image for (u, v) falling between pixel centers
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextureFilter {
+    /// Color of the closest pixel - sharp, but blocky when magnified
+    Nearest,
+    /// Weighted average of the four closest pixels - smooth when magnified
+    Bilinear,
+}
+
+/// Image wrapped around a shape - the point is flattened into (u, v) by the mapping and the
+/// color is read from the canvas. (0, 0) is the bottom left corner of the image
+#[derive(Debug)]
+pub struct Texture {
+    transform: Matrix4,
+    canvas: Rc<Canvas>,
+    mapping: UvMapping,
+    filter: TextureFilter,
+}
+
+impl Texture {
+    pub fn new(canvas: Rc<Canvas>, mapping: UvMapping, filter: TextureFilter) -> Texture {
+        Texture {
+            transform: Matrix4::identity_matrix(),
+            canvas,
+            mapping,
+            filter,
+        }
+    }
+
+    pub fn get_mapping(&self) -> UvMapping {
+        self.mapping
+    }
+
+    pub fn get_filter(&self) -> TextureFilter {
+        self.filter
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Color {
+        // coordinates are clamped by the callers, so reading can't fail
+        self.canvas
+            .pixel_at(x, y)
+            .expect("Texture sampled outside of canvas border")
+    }
+
+    /// Color of the image at (u, v); both are clamped to [0, 1]
+    pub fn sample(&self, u: f64, v: f64) -> Color {
+        let width = self.canvas.get_width();
+        let height = self.canvas.get_height();
+        if width == 0 || height == 0 {
+            return BLACK;
+        }
+        let u = u.clamp(0.0, 1.0);
+        // rows of canvas go from top to bottom, while v goes upwards
+        let v = 1.0 - v.clamp(0.0, 1.0);
+        let x = u * (width - 1) as f64;
+        let y = v * (height - 1) as f64;
+        match self.filter {
+            TextureFilter::Nearest => self.pixel(x.round() as usize, y.round() as usize),
+            TextureFilter::Bilinear => {
+                let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+                let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+                let (fx, fy) = (x - x0 as f64, y - y0 as f64);
+                let top = self.pixel(x0, y0) * (1.0 - fx) + self.pixel(x1, y0) * fx;
+                let bottom = self.pixel(x0, y1) * (1.0 - fx) + self.pixel(x1, y1) * fx;
+                top * (1.0 - fy) + bottom * fy
+            }
+        }
+    }
+}
+
+impl Pattern for Texture {
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn pattern_at(&self, p: Tuple) -> Result<Color, MatrixError> {
+        let (u, v) = self.mapping.map(p);
+        Ok(self.sample(u, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::WHITE, tuple::point};
+
+    /// 2x2 image: red and green in the top row, blue and white in the bottom one
+    fn image() -> Rc<Canvas> {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0)).unwrap();
+        c.write_pixel(1, 0, Color::new(0.0, 1.0, 0.0)).unwrap();
+        c.write_pixel(0, 1, Color::new(0.0, 0.0, 1.0)).unwrap();
+        c.write_pixel(1, 1, WHITE).unwrap();
+        Rc::new(c)
+    }
+
+    #[test]
+    fn nearest_filter_picks_closest_pixel() {
+        let t = Texture::new(image(), UvMapping::Planar, TextureFilter::Nearest);
+        let cases = [
+            (0.0, 1.0, Color::new(1.0, 0.0, 0.0)),
+            (0.9, 0.8, Color::new(0.0, 1.0, 0.0)),
+            (0.2, 0.1, Color::new(0.0, 0.0, 1.0)),
+            (1.0, 0.0, WHITE),
+            // out of range coordinates are clamped
+            (1.5, -0.5, WHITE),
+        ];
+        for (u, v, color) in cases.iter() {
+            assert_eq!(*color, t.sample(*u, *v));
+        }
+    }
+
+    #[test]
+    fn bilinear_filter_blends_neighbouring_pixels() {
+        let t = Texture::new(image(), UvMapping::Planar, TextureFilter::Bilinear);
+        let cases = [
+            (0.0, 1.0, Color::new(1.0, 0.0, 0.0)),
+            (0.5, 1.0, Color::new(0.5, 0.5, 0.0)),
+            (0.0, 0.5, Color::new(0.5, 0.0, 0.5)),
+            (0.5, 0.5, Color::new(0.5, 0.5, 0.5)),
+            (1.0, 0.0, WHITE),
+        ];
+        for (u, v, color) in cases.iter() {
+            assert_eq!(*color, t.sample(*u, *v));
+        }
+    }
+
+    #[test]
+    fn texture_pattern_uses_mapping() {
+        let t = Texture::new(image(), UvMapping::Planar, TextureFilter::Nearest);
+        // planar mapping: u from x, v from z
+        assert_eq!(
+            Color::new(0.0, 0.0, 1.0),
+            t.pattern_at(point(0.1, 5.0, 0.1)).unwrap()
+        );
+        assert_eq!(
+            Color::new(0.0, 1.0, 0.0),
+            t.pattern_at(point(0.9, 0.0, 0.9)).unwrap()
+        );
+    }
+
+    #[test]
+    fn spherical_texture_maps_poles_to_top_and_bottom_rows() {
+        let t = Texture::new(image(), UvMapping::Spherical, TextureFilter::Nearest);
+        let north = t.pattern_at(point(0.0, 1.0, 0.0)).unwrap();
+        let south = t.pattern_at(point(0.0, -1.0, 0.0)).unwrap();
+        // u is 0.5 at both poles, which rounds to the second column
+        assert_eq!(Color::new(0.0, 1.0, 0.0), north);
+        assert_eq!(WHITE, south);
+    }
+}
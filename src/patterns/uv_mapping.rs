@@ -0,0 +1,223 @@
+use crate::{
+    tuple::{magnitude, vector, Tuple, TupleT},
+    utils::PI,
+};
+
+/// Ways of flattening a point on the surface of a shape into two dimensional (u, v)
+/// coordinates, both in range [0, 1]; u grows to the right and v grows upwards
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UvMapping {
+    /// For spheres - u goes around the y axis, v from the south to the north pole
+    Spherical,
+    /// For planes - image repeats every unit in x (u) and z (v)
+    Planar,
+    /// For cylinders - u goes around the y axis, image repeats every unit in y (v)
+    Cylindrical,
+    /// For cubes - every face is mapped to the whole image
+    Cube,
+}
+
+/// Face of the cube from `Cube` shape - faces are named as seen from the negative z axis
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl UvMapping {
+    pub fn map(&self, p: Tuple) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(p),
+            UvMapping::Planar => planar_map(p),
+            UvMapping::Cylindrical => cylindrical_map(p),
+            UvMapping::Cube => {
+                let (_, u, v) = cube_map(p);
+                (u, v)
+            }
+        }
+    }
+}
+
+pub fn spherical_map(p: Tuple) -> (f64, f64) {
+    // azimuthal angle, in range (-PI, PI] - increases clockwise when viewed from above
+    let theta = p.get_x().atan2(p.get_z());
+    let radius = magnitude(&vector(p.get_x(), p.get_y(), p.get_z()));
+    // polar angle, in range [0, PI]
+    let phi = (p.get_y() / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+    // fixing the direction of u, so it increases counter-clockwise when viewed from above
+    let u = 1.0 - (raw_u + 0.5);
+    // subtracting from 1, so v is 1 at the north pole
+    let v = 1.0 - phi / PI;
+    (u, v)
+}
+
+pub fn planar_map(p: Tuple) -> (f64, f64) {
+    (p.get_x().rem_euclid(1.0), p.get_z().rem_euclid(1.0))
+}
+
+pub fn cylindrical_map(p: Tuple) -> (f64, f64) {
+    let theta = p.get_x().atan2(p.get_z());
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+    (u, p.get_y().rem_euclid(1.0))
+}
+
+/// Face of the cube the point lies on - the one perpendicular to the largest coordinate
+pub fn face_from_point(p: Tuple) -> CubeFace {
+    let (x, y, z) = (p.get_x(), p.get_y(), p.get_z());
+    let coord = x.abs().max(y.abs()).max(z.abs());
+    if coord == x {
+        CubeFace::Right
+    } else if coord == -x {
+        CubeFace::Left
+    } else if coord == y {
+        CubeFace::Up
+    } else if coord == -y {
+        CubeFace::Down
+    } else if coord == z {
+        CubeFace::Front
+    } else {
+        CubeFace::Back
+    }
+}
+
+/// Face the point lies on together with (u, v) coordinates on that face
+pub fn cube_map(p: Tuple) -> (CubeFace, f64, f64) {
+    let (x, y, z) = (p.get_x(), p.get_y(), p.get_z());
+    let face = face_from_point(p);
+    let (u, v) = match face {
+        CubeFace::Front => (x + 1.0, y + 1.0),
+        CubeFace::Back => (1.0 - x, y + 1.0),
+        CubeFace::Left => (z + 1.0, y + 1.0),
+        CubeFace::Right => (1.0 - z, y + 1.0),
+        CubeFace::Up => (x + 1.0, 1.0 - z),
+        CubeFace::Down => (x + 1.0, z + 1.0),
+    };
+    (face, u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tuple::point, utils::eq_with_eps};
+
+    fn assert_uv(expected: (f64, f64), actual: (f64, f64)) {
+        assert!(
+            eq_with_eps(expected.0, actual.0),
+            "{:?} {:?}",
+            expected,
+            actual
+        );
+        assert!(
+            eq_with_eps(expected.1, actual.1),
+            "{:?} {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn using_spherical_mapping_on_3d_point() {
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let cases = [
+            (point(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (point(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (point(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (point(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (point(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (point(half_sqrt, half_sqrt, 0.0), (0.25, 0.75)),
+        ];
+        for (p, uv) in cases.iter() {
+            assert_uv(*uv, spherical_map(*p));
+        }
+    }
+
+    #[test]
+    fn using_planar_mapping_on_3d_point() {
+        let cases = [
+            (point(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (point(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (point(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (point(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (point(0.25, 0.0, -1.75), (0.25, 0.25)),
+            (point(1.0, 0.0, -1.0), (0.0, 0.0)),
+            (point(0.0, 0.0, 0.0), (0.0, 0.0)),
+        ];
+        for (p, uv) in cases.iter() {
+            assert_uv(*uv, planar_map(*p));
+        }
+    }
+
+    #[test]
+    fn using_cylindrical_mapping_on_3d_point() {
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let cases = [
+            (point(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (point(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (point(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (point(half_sqrt, 0.5, -half_sqrt), (0.125, 0.5)),
+            (point(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (point(half_sqrt, 0.5, half_sqrt), (0.375, 0.5)),
+            (point(0.0, -0.25, 1.0), (0.5, 0.75)),
+            (point(-half_sqrt, 0.5, half_sqrt), (0.625, 0.5)),
+            (point(-1.0, 1.25, 0.0), (0.75, 0.25)),
+            (point(-half_sqrt, 0.5, -half_sqrt), (0.875, 0.5)),
+        ];
+        for (p, uv) in cases.iter() {
+            assert_uv(*uv, cylindrical_map(*p));
+        }
+    }
+
+    #[test]
+    fn identifying_face_of_cube_from_point() {
+        let cases = [
+            (point(-1.0, 0.5, -0.25), CubeFace::Left),
+            (point(1.1, -0.75, 0.8), CubeFace::Right),
+            (point(0.1, 0.6, 0.9), CubeFace::Front),
+            (point(-0.7, 0.0, -2.0), CubeFace::Back),
+            (point(0.5, 1.0, 0.9), CubeFace::Up),
+            (point(-0.2, -1.3, 1.1), CubeFace::Down),
+        ];
+        for (p, face) in cases.iter() {
+            assert_eq!(*face, face_from_point(*p));
+        }
+    }
+
+    #[test]
+    fn uv_mapping_of_cube_faces() {
+        let cases = [
+            (point(-0.5, 0.5, 1.0), CubeFace::Front, (0.25, 0.75)),
+            (point(0.5, -0.5, 1.0), CubeFace::Front, (0.75, 0.25)),
+            (point(0.5, 0.5, -1.0), CubeFace::Back, (0.25, 0.75)),
+            (point(-0.5, -0.5, -1.0), CubeFace::Back, (0.75, 0.25)),
+            (point(-1.0, 0.5, -0.5), CubeFace::Left, (0.25, 0.75)),
+            (point(-1.0, -0.5, 0.5), CubeFace::Left, (0.75, 0.25)),
+            (point(1.0, 0.5, 0.5), CubeFace::Right, (0.25, 0.75)),
+            (point(1.0, -0.5, -0.5), CubeFace::Right, (0.75, 0.25)),
+            (point(-0.5, 1.0, -0.5), CubeFace::Up, (0.25, 0.75)),
+            (point(0.5, 1.0, 0.5), CubeFace::Up, (0.75, 0.25)),
+            (point(-0.5, -1.0, 0.5), CubeFace::Down, (0.25, 0.75)),
+            (point(0.5, -1.0, -0.5), CubeFace::Down, (0.75, 0.25)),
+        ];
+        for (p, face, uv) in cases.iter() {
+            let (f, u, v) = cube_map(*p);
+            assert_eq!(*face, f);
+            assert_uv(*uv, (u, v));
+        }
+    }
+
+    #[test]
+    fn mapping_enum_dispatches_to_mapping_functions() {
+        let p = point(0.5, 1.0, 0.5);
+        assert_uv(spherical_map(p), UvMapping::Spherical.map(p));
+        assert_uv(planar_map(p), UvMapping::Planar.map(p));
+        assert_uv(cylindrical_map(p), UvMapping::Cylindrical.map(p));
+        assert_uv((0.75, 0.25), UvMapping::Cube.map(p));
+    }
+}
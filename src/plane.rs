@@ -0,0 +1,201 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{point, vector, Tuple, TupleT},
+    utils::EPSILON,
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+// Plane is infinite and lies on xz plane in object space
+#[derive(Debug, Clone)]
+pub struct Plane {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+}
+
+impl Shape for Plane {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Plane
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        // ray parallel to the plane never hits it; coplanar ray is treated as a miss as well,
+        // since plane is infinitely thin
+        if ray.direction.get_y().abs() >= EPSILON {
+            xs.add(Intersection::new(
+                -ray.origin.get_y() / ray.direction.get_y(),
+                self,
+            ));
+        }
+        Ok(xs)
+    }
+
+    /// Every point of the plane has the same normal
+    fn local_normal_at(&self, _object_point: Tuple) -> Tuple {
+        vector(0.0, 1.0, 0.0)
+    }
+
+    /// Plane is infinite in both x and z, but has no thickness
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            point(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Plane {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Plane {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{intersections::intersect, transformations::translation, utils::eq_with_eps};
+
+    #[test]
+    fn plane_has_plane_shape_type() {
+        let p = Plane::default();
+        assert_eq!(ShapeType::Plane, p.get_shape_type());
+    }
+
+    #[test]
+    fn normal_of_plane_is_constant_everywhere() {
+        let p = Plane::default();
+        let n1 = p.local_normal_at(point(0.0, 0.0, 0.0));
+        let n2 = p.local_normal_at(point(10.0, 0.0, -10.0));
+        let n3 = p.local_normal_at(point(-5.0, 0.0, 150.0));
+        assert_eq!(vector(0.0, 1.0, 0.0), n1);
+        assert_eq!(vector(0.0, 1.0, 0.0), n2);
+        assert_eq!(vector(0.0, 1.0, 0.0), n3);
+    }
+
+    #[test]
+    fn intersect_with_ray_parallel_to_plane() {
+        let p = Rc::new(Plane::default());
+        let r = Ray {
+            origin: point(0.0, 10.0, 0.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = p.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn intersect_with_coplanar_ray() {
+        let p = Rc::new(Plane::default());
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = p.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_above() {
+        let p = Plane::default();
+        let r = Ray {
+            origin: point(0.0, 1.0, 0.0),
+            direction: vector(0.0, -1.0, 0.0),
+        };
+        let xs = Rc::new(p.clone()).local_intersect(&r).unwrap();
+        assert_eq!(1, xs.len());
+        assert!(eq_with_eps(1.0, xs[0].t));
+        assert_eq!(p.get_id(), xs[0].object.borrow().get_id());
+    }
+
+    #[test]
+    fn ray_intersecting_plane_from_below() {
+        let p = Plane::default();
+        let r = Ray {
+            origin: point(0.0, -1.0, 0.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        let xs = Rc::new(p.clone()).local_intersect(&r).unwrap();
+        assert_eq!(1, xs.len());
+        assert!(eq_with_eps(1.0, xs[0].t));
+        assert_eq!(p.get_id(), xs[0].object.borrow().get_id());
+    }
+
+    #[test]
+    fn intersecting_transformed_plane() {
+        let mut p = Plane::default();
+        p.set_transform(translation(0.0, 2.0, 0.0));
+        let r = Ray {
+            origin: point(0.0, 5.0, 0.0),
+            direction: vector(0.0, -1.0, 0.0),
+        };
+        let xs = intersect(Rc::new(p), &r).unwrap();
+        assert_eq!(1, xs.len());
+        assert!(eq_with_eps(3.0, xs[0].t));
+    }
+
+    #[test]
+    fn plane_has_bounding_box() {
+        let p = Plane::default();
+        let b = p.bounds();
+        assert_eq!(f64::NEG_INFINITY, b.get_min().get_x());
+        assert_eq!(0.0, b.get_min().get_y());
+        assert_eq!(f64::NEG_INFINITY, b.get_min().get_z());
+        assert_eq!(f64::INFINITY, b.get_max().get_x());
+        assert_eq!(0.0, b.get_max().get_y());
+        assert_eq!(f64::INFINITY, b.get_max().get_z());
+    }
+}
@@ -0,0 +1,398 @@
+use crate::{
+    canvas::{scale_color, BitDepth, Canvas},
+    tone_mapping::OutputTransform,
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+/// Largest amount of data a single stored deflate block can hold
+const MAX_STORED_BLOCK: usize = 65535;
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(chunks: &[&[u8]]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in chunks.iter().flat_map(|c| c.iter()) {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+/// Running checksum of uncompressed data, stored at the end of zlib stream
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        // 5552 bytes is the most that can be summed before b overflows
+        for chunk in data.chunks(5552) {
+            for byte in chunk {
+                self.a += *byte as u32;
+                self.b += self.a;
+            }
+            self.a %= 65521;
+            self.b %= 65521;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    out.write_all(&crc32(&[kind, data]).to_be_bytes())
+}
+
+/// Packs image data into zlib stream made of stored (uncompressed) deflate blocks, emitting every
+/// block as soon as it fills up as separate IDAT chunk
+struct IdatWriter<'a, W: Write> {
+    out: &'a mut W,
+    remaining: usize,
+    block: Vec<u8>,
+    adler: Adler32,
+    started: bool,
+}
+
+impl<'a, W: Write> IdatWriter<'a, W> {
+    fn new(out: &'a mut W, total: usize) -> IdatWriter<'a, W> {
+        IdatWriter {
+            out,
+            remaining: total,
+            block: Vec::with_capacity(MAX_STORED_BLOCK),
+            adler: Adler32::new(),
+            started: false,
+        }
+    }
+
+    fn write(&mut self, mut data: &[u8]) -> io::Result<()> {
+        self.adler.update(data);
+        while !data.is_empty() {
+            let n = data.len().min(MAX_STORED_BLOCK - self.block.len());
+            self.block.extend_from_slice(&data[..n]);
+            data = &data[n..];
+            if self.block.len() == MAX_STORED_BLOCK {
+                self.flush_block()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_block(&mut self) -> io::Result<()> {
+        self.remaining -= self.block.len();
+        let last = self.remaining == 0;
+        let mut chunk = Vec::with_capacity(self.block.len() + 11);
+        if !self.started {
+            // deflate with 32K window, no preset dictionary, header check bits
+            chunk.extend_from_slice(&[0x78, 0x01]);
+            self.started = true;
+        }
+        let len = self.block.len() as u16;
+        chunk.push(last as u8);
+        chunk.extend_from_slice(&len.to_le_bytes());
+        chunk.extend_from_slice(&(!len).to_le_bytes());
+        chunk.append(&mut self.block);
+        if last {
+            chunk.extend_from_slice(&self.adler.finish().to_be_bytes());
+        }
+        write_chunk(self.out, b"IDAT", &chunk)
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        if !self.block.is_empty() {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+}
+
+/// Streams canvas as PNG image, row by row. Colors go through the output transform and are
+/// scaled to the bit depth. `alpha`, if given, holds opacity of every pixel in row-major order
+/// and adds alpha channel to the image. Data isn't compressed, so files are about the size of
+/// raw PPM
+pub fn write_png<W: Write>(
+    c: &Canvas,
+    depth: BitDepth,
+    alpha: Option<&[f64]>,
+    transform: &OutputTransform,
+    out: &mut W,
+) -> io::Result<()> {
+    let (width, height) = (c.get_width(), c.get_height());
+    if width == 0 || height == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "PNG image can't be empty",
+        ));
+    }
+    // dimensions are stored as 4 byte integers, but must not exceed 2^31 - 1
+    if width > i32::MAX as usize || height > i32::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "PNG image can't be wider or higher than 2^31 - 1 pixels",
+        ));
+    }
+    if alpha.is_some_and(|a| a.len() != width * height) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Alpha values don't match canvas size",
+        ));
+    }
+    let (bits, bytes_per_sample) = match depth {
+        BitDepth::Eight => (8, 1),
+        BitDepth::Sixteen => (16, 2),
+    };
+    let channels = if alpha.is_some() { 4 } else { 3 };
+    // truecolor or truecolor with alpha
+    let color_type = if alpha.is_some() { 6 } else { 2 };
+
+    out.write_all(&SIGNATURE)?;
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // no compression method, filtering method or interlace other than the default ones exist
+    header.extend_from_slice(&[bits, color_type, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+
+    let max = depth.max_value() as f64;
+    let row_length = 1 + width * channels * bytes_per_sample;
+    let mut idat = IdatWriter::new(out, row_length * height);
+    let mut row = Vec::with_capacity(row_length);
+    for (y, pixels) in c.rows().enumerate() {
+        row.clear();
+        // filter type none
+        row.push(0);
+        for (x, pixel) in pixels.iter().enumerate() {
+            let pixel = transform.apply(*pixel);
+            let mut samples = vec![pixel.get_red(), pixel.get_green(), pixel.get_blue()];
+            if let Some(alpha) = alpha {
+                samples.push(alpha[y * width + x]);
+            }
+            for sample in samples {
+                let scaled = scale_color(sample, max);
+                match depth {
+                    BitDepth::Eight => row.push(scaled as u8),
+                    BitDepth::Sixteen => row.extend_from_slice(&(scaled as u16).to_be_bytes()),
+                }
+            }
+        }
+        idat.write(&row)?;
+    }
+    idat.finish()?;
+    write_chunk(out, b"IEND", &[])?;
+    out.flush()
+}
+
+pub fn save_png(
+    c: &Canvas,
+    path: &str,
+    depth: BitDepth,
+    alpha: Option<&[f64]>,
+    transform: &OutputTransform,
+) -> io::Result<()> {
+    write_png(
+        c,
+        depth,
+        alpha,
+        transform,
+        &mut BufWriter::new(File::create(path)?),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    /// Splits PNG into (type, data) chunks, checking their CRCs
+    fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(SIGNATURE, png[..8]);
+        let mut result = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes([png[pos], png[pos + 1], png[pos + 2], png[pos + 3]]);
+            let kind = &png[pos + 4..pos + 8];
+            let data = &png[pos + 8..pos + 8 + len as usize];
+            let crc = &png[pos + 8 + len as usize..pos + 12 + len as usize];
+            assert_eq!(crc32(&[kind, data]).to_be_bytes(), crc);
+            result.push((String::from_utf8(kind.to_vec()).unwrap(), data.to_vec()));
+            pos += 12 + len as usize;
+        }
+        result
+    }
+
+    /// Decodes zlib stream made of stored blocks, checking the Adler-32 checksum
+    fn inflate_stored(stream: &[u8]) -> Vec<u8> {
+        assert_eq!([0x78, 0x01], stream[..2]);
+        assert_eq!(0, u16::from_be_bytes([stream[0], stream[1]]) % 31);
+        let mut result = Vec::new();
+        let mut pos = 2;
+        loop {
+            let last = stream[pos] & 1 == 1;
+            assert_eq!(0, stream[pos] >> 1);
+            let len = u16::from_le_bytes([stream[pos + 1], stream[pos + 2]]);
+            let nlen = u16::from_le_bytes([stream[pos + 3], stream[pos + 4]]);
+            assert_eq!(!len, nlen);
+            result.extend_from_slice(&stream[pos + 5..pos + 5 + len as usize]);
+            pos += 5 + len as usize;
+            if last {
+                break;
+            }
+        }
+        let mut adler = Adler32::new();
+        adler.update(&result);
+        assert_eq!(adler.finish().to_be_bytes(), stream[pos..pos + 4]);
+        assert_eq!(pos + 4, stream.len());
+        result
+    }
+
+    fn image_data(png: &[u8]) -> Vec<u8> {
+        let stream: Vec<u8> = chunks(png)
+            .into_iter()
+            .filter(|(kind, _)| kind == "IDAT")
+            .flat_map(|(_, data)| data)
+            .collect();
+        inflate_stored(&stream)
+    }
+
+    #[test]
+    fn computing_checksums() {
+        assert_eq!(0xCBF4_3926, crc32(&[b"123456789"]));
+        assert_eq!(0xAE42_6082, crc32(&[b"IEND"]));
+        let mut adler = Adler32::new();
+        adler.update(b"Wikipedia");
+        assert_eq!(0x11E6_0398, adler.finish());
+    }
+
+    #[test]
+    fn writing_png_header_and_chunks() {
+        let c = Canvas::new(5, 3);
+        let mut out = Vec::new();
+        write_png(
+            &c,
+            BitDepth::Eight,
+            None,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        let chunks = chunks(&out);
+        let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(vec!["IHDR", "IDAT", "IEND"], kinds);
+        assert_eq!(vec![0, 0, 0, 5, 0, 0, 0, 3, 8, 2, 0, 0, 0], chunks[0].1);
+        assert!(chunks[2].1.is_empty());
+    }
+
+    #[test]
+    fn writing_eight_bit_rgb_pixels() {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(0, 0, Color::new(1.5, 0.0, 0.2)).unwrap();
+        c.write_pixel(1, 1, Color::new(-0.5, 0.5, 1.0)).unwrap();
+        let mut out = Vec::new();
+        write_png(
+            &c,
+            BitDepth::Eight,
+            None,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![0, 255, 0, 51, 0, 0, 0, 0, 0, 0, 0, 0, 128, 255],
+            image_data(&out)
+        );
+    }
+
+    #[test]
+    fn writing_sixteen_bit_pixels_with_alpha() {
+        let c = Canvas::new_with_color(1, 1, Color::new(1.0, 0.5, 0.0));
+        let mut out = Vec::new();
+        write_png(
+            &c,
+            BitDepth::Sixteen,
+            Some(&[0.2]),
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(16, chunks(&out)[0].1[8]);
+        assert_eq!(6, chunks(&out)[0].1[9]);
+        assert_eq!(
+            vec![0, 0xFF, 0xFF, 0x80, 0x00, 0x00, 0x00, 0x33, 0x33],
+            image_data(&out)
+        );
+    }
+
+    #[test]
+    fn large_images_are_split_into_multiple_blocks() {
+        let c = Canvas::new_with_color(300, 100, Color::new(0.2, 0.4, 0.6));
+        let mut out = Vec::new();
+        write_png(
+            &c,
+            BitDepth::Sixteen,
+            None,
+            &OutputTransform::default(),
+            &mut out,
+        )
+        .unwrap();
+        let idats = chunks(&out)
+            .iter()
+            .filter(|(kind, _)| kind == "IDAT")
+            .count();
+        assert_eq!(3, idats);
+        let data = image_data(&out);
+        assert_eq!((1 + 300 * 6) * 100, data.len());
+        assert_eq!([0, 0x33, 0x33, 0x66, 0x66, 0x99, 0x99], data[1801..1808]);
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        let mut out = Vec::new();
+        assert!(write_png(
+            &Canvas::new(0, 0),
+            BitDepth::Eight,
+            None,
+            &OutputTransform::default(),
+            &mut out
+        )
+        .is_err());
+        assert!(write_png(
+            &Canvas::new(2, 2),
+            BitDepth::Eight,
+            Some(&[1.0]),
+            &OutputTransform::default(),
+            &mut out
+        )
+        .is_err());
+    }
+}
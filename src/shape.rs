@@ -1,28 +1,96 @@
 use crate::{
+    bounds::BoundingBox,
     color::Color,
+    intersections::{Intersection, Intersections},
     material::Material,
     matrix::{Matrix4, MatrixError},
-    tuple::Tuple,
+    ray::Ray,
+    tuple::{magnitude, normalize, Tuple, TupleT},
 };
-use std::{any::Any, fmt};
+use std::{any::Any, fmt, rc::Rc};
 use uuid::Uuid;
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ShapeType {
     Sphere,
+    Plane,
+    Cube,
+    Cylinder,
+    Cone,
+    Triangle,
+    SmoothTriangle,
+    Group,
+    Csg,
+    #[cfg(test)]
+    Test,
 }
 
 pub trait Shape {
     fn as_any(&self) -> &dyn Any;
+    fn get_shape_type(&self) -> ShapeType;
     fn get_transform(&self) -> Matrix4;
     fn set_transform(&mut self, transform: Matrix4);
-    fn normal_at(&self, p: Tuple) -> Result<Tuple, MatrixError>;
+    /// Intersects shape with a ray already converted to object space
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError>;
+    /// Normal at a point given in object space
+    fn local_normal_at(&self, p: Tuple) -> Tuple;
+    /// Normal at a point given in object space for intersection with barycentric coordinates u
+    /// and v; only smooth triangles interpolate their normals - other shapes ignore them
+    fn local_normal_at_uv(&self, p: Tuple, _u: f64, _v: f64) -> Tuple {
+        self.local_normal_at(p)
+    }
+    /// Axis-aligned box containing the whole shape in object space
+    fn bounds(&self) -> BoundingBox;
     fn set_material(&mut self, m: Material);
     fn get_material(&self) -> &Material;
     fn get_color(&self) -> &Color;
     fn set_color(&mut self, c: Color);
     fn set_ambient(&mut self, a: f64);
     fn get_id(&self) -> &Uuid;
+
+    /// Checks if other shape is this shape or, for aggregate shapes, one of its descendants
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.get_id() == other.get_id()
+    }
+
+    /// Bounds of the shape in its parent's space - the box in object space transformed by the
+    /// shape's transformation
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.get_transform())
+    }
+
+    /// Reorganizes children of aggregate shapes into a hierarchy of subgroups, so that whole
+    /// subgroups can be skipped when a ray misses their bounds; primitives have nothing to divide
+    fn divide(&mut self, _threshold: usize) {}
+
+    /// Normal at point on shape is a vector perpendicular to the surface - it's the normal
+    fn normal_at(&self, world_point: Tuple) -> Result<Tuple, MatrixError> {
+        // converting point from world space to object space by multiplying point by inverse of
+        // transformation matrix
+        let inverse = self.get_transform().inverse()?;
+        let local_normal = self.local_normal_at(inverse * world_point);
+        normal_to_world(inverse, local_normal)
+    }
+
+    /// Same as normal_at, but takes into account where the shape was hit - barycentric
+    /// coordinates of the intersection and transformations of groups containing the shape
+    fn normal_at_hit(&self, world_point: Tuple, hit: &Intersection) -> Result<Tuple, MatrixError> {
+        let inverse = (hit.parent_transform * self.get_transform()).inverse()?;
+        let local_normal = self.local_normal_at_uv(inverse * world_point, hit.u, hit.v);
+        normal_to_world(inverse, local_normal)
+    }
+}
+
+fn normal_to_world(inverse: Matrix4, local_normal: Tuple) -> Result<Tuple, MatrixError> {
+    let mut world_normal = inverse.transpose()? * local_normal;
+    // hack - in order to avoid multiplication and inversing a submatrix of transformation,
+    // parameter w is set by hand to 0; otherwise some transformation might corrupt that value
+    world_normal.set_w(0.0);
+    // shapes without surface of their own (groups, CSG) have zero normal, which can't be normalized
+    if magnitude(&world_normal) == 0.0 {
+        return Ok(world_normal);
+    }
+    Ok(normalize(&world_normal))
 }
 
 impl fmt::Debug for dyn Shape {
@@ -41,3 +109,157 @@ impl PartialEq for dyn Shape {
         self.get_id() == other.get_id()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        intersections::intersect,
+        transformations::{rotation_z, scaling, translation},
+        tuple::{point, vector},
+        utils::PI,
+    };
+    use std::cell::Cell;
+
+    /// Shape without any geometry - it only records the ray it was intersected with, so the
+    /// shared world/object space conversion can be tested
+    struct TestShape {
+        id: Uuid,
+        transform: Matrix4,
+        material: Material,
+        saved_ray: Cell<Option<Ray>>,
+    }
+
+    impl Default for TestShape {
+        fn default() -> Self {
+            TestShape {
+                id: Uuid::new_v4(),
+                transform: Matrix4::identity_matrix(),
+                material: Material::default(),
+                saved_ray: Cell::new(None),
+            }
+        }
+    }
+
+    impl Shape for TestShape {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn get_shape_type(&self) -> ShapeType {
+            ShapeType::Test
+        }
+
+        fn get_transform(&self) -> Matrix4 {
+            self.transform
+        }
+
+        fn set_transform(&mut self, transform: Matrix4) {
+            self.transform = transform;
+        }
+
+        fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+            self.saved_ray.set(Some(*ray));
+            Ok(Intersections::new())
+        }
+
+        fn local_normal_at(&self, p: Tuple) -> Tuple {
+            vector(p.get_x(), p.get_y(), p.get_z())
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0))
+        }
+
+        fn set_material(&mut self, m: Material) {
+            self.material = m;
+        }
+
+        fn get_material(&self) -> &Material {
+            &self.material
+        }
+
+        fn get_color(&self) -> &Color {
+            &self.material.color
+        }
+
+        fn set_color(&mut self, c: Color) {
+            self.material.color = c;
+        }
+
+        fn set_ambient(&mut self, a: f64) {
+            self.material.ambient = a;
+        }
+
+        fn get_id(&self) -> &Uuid {
+            &self.id
+        }
+    }
+
+    #[test]
+    fn intersecting_scaled_shape_with_ray() {
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let mut s = TestShape::default();
+        s.set_transform(scaling(2.0, 2.0, 2.0));
+        let s = Rc::new(s);
+        intersect(s.clone(), &r).unwrap();
+        let saved_ray = s.saved_ray.get().unwrap();
+        assert_eq!(point(0.0, 0.0, -2.5), saved_ray.origin);
+        assert_eq!(vector(0.0, 0.0, 0.5), saved_ray.direction);
+    }
+
+    #[test]
+    fn intersecting_translated_shape_with_ray() {
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let mut s = TestShape::default();
+        s.set_transform(translation(5.0, 0.0, 0.0));
+        let s = Rc::new(s);
+        intersect(s.clone(), &r).unwrap();
+        let saved_ray = s.saved_ray.get().unwrap();
+        assert_eq!(point(-5.0, 0.0, -5.0), saved_ray.origin);
+        assert_eq!(vector(0.0, 0.0, 1.0), saved_ray.direction);
+    }
+
+    #[test]
+    fn computing_normal_on_translated_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(translation(0.0, 1.0, 0.0));
+        let n = s
+            .normal_at(point(0.0, 1.70711, -std::f64::consts::FRAC_1_SQRT_2))
+            .unwrap();
+        assert_eq!(
+            vector(
+                0.0,
+                std::f64::consts::FRAC_1_SQRT_2,
+                -std::f64::consts::FRAC_1_SQRT_2
+            ),
+            n
+        );
+    }
+
+    #[test]
+    fn computing_normal_on_transformed_shape() {
+        let mut s = TestShape::default();
+        s.set_transform(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0));
+        let two_sqrt = 2.0f64.sqrt();
+        let n = s
+            .normal_at(point(0.0, two_sqrt / 2.0, -two_sqrt / 2.0))
+            .unwrap();
+        assert_eq!(vector(0.0, 0.97014, -0.24254), n);
+    }
+
+    #[test]
+    fn querying_shape_bounding_box_in_parent_space() {
+        let mut s = TestShape::default();
+        s.set_transform(translation(1.0, -3.0, 5.0) * scaling(0.5, 2.0, 4.0));
+        let b = s.parent_space_bounds();
+        assert_eq!(point(0.5, -5.0, 1.0), b.get_min());
+        assert_eq!(point(1.5, -1.0, 9.0), b.get_max());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    triangle::intersect_triangle,
+    tuple::{cross, normalize, Tuple},
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+/// Triangle with a normal assigned to each vertex - normal at any point is interpolated from
+/// them, so a mesh of such triangles looks smooth
+#[derive(Debug, Clone)]
+pub struct SmoothTriangle {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    n1: Tuple,
+    n2: Tuple,
+    n3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> SmoothTriangle {
+        SmoothTriangle {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        }
+    }
+
+    pub fn get_p1(&self) -> Tuple {
+        self.p1
+    }
+
+    pub fn get_p2(&self) -> Tuple {
+        self.p2
+    }
+
+    pub fn get_p3(&self) -> Tuple {
+        self.p3
+    }
+
+    pub fn get_n1(&self) -> Tuple {
+        self.n1
+    }
+
+    pub fn get_n2(&self) -> Tuple {
+        self.n2
+    }
+
+    pub fn get_n3(&self) -> Tuple {
+        self.n3
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::SmoothTriangle
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        if let Some((t, u, v)) = intersect_triangle(self.p1, self.e1, self.e2, ray) {
+            xs.add(Intersection::new_with_uv(t, self, u, v));
+        }
+        Ok(xs)
+    }
+
+    /// Without barycentric coordinates there is nothing to interpolate - normal of the flat
+    /// triangle is returned
+    fn local_normal_at(&self, _object_point: Tuple) -> Tuple {
+        normalize(&cross(&self.e2, &self.e1))
+    }
+
+    fn local_normal_at_uv(&self, _object_point: Tuple, u: f64, v: f64) -> Tuple {
+        self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut b = BoundingBox::default();
+        b.add_point(self.p1);
+        b.add_point(self.p2);
+        b.add_point(self.p3);
+        b
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for SmoothTriangle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        intersections::{intersect, Computations},
+        tuple::{point, vector},
+        utils::eq_with_eps,
+    };
+
+    fn setup() -> SmoothTriangle {
+        SmoothTriangle::new(
+            point(0.0, 1.0, 0.0),
+            point(-1.0, 0.0, 0.0),
+            point(1.0, 0.0, 0.0),
+            vector(0.0, 1.0, 0.0),
+            vector(-1.0, 0.0, 0.0),
+            vector(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_smooth_triangle() {
+        let tri = setup();
+        assert_eq!(ShapeType::SmoothTriangle, tri.get_shape_type());
+        assert_eq!(point(0.0, 1.0, 0.0), tri.get_p1());
+        assert_eq!(point(-1.0, 0.0, 0.0), tri.get_p2());
+        assert_eq!(point(1.0, 0.0, 0.0), tri.get_p3());
+        assert_eq!(vector(0.0, 1.0, 0.0), tri.get_n1());
+        assert_eq!(vector(-1.0, 0.0, 0.0), tri.get_n2());
+        assert_eq!(vector(1.0, 0.0, 0.0), tri.get_n3());
+    }
+
+    #[test]
+    fn intersection_with_smooth_triangle_stores_u_v() {
+        let tri = Rc::new(setup());
+        let r = Ray {
+            origin: point(-0.2, 0.3, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = tri.local_intersect(&r).unwrap();
+        assert!(eq_with_eps(0.45, xs[0].u));
+        assert!(eq_with_eps(0.25, xs[0].v));
+    }
+
+    #[test]
+    fn smooth_triangle_uses_u_v_to_interpolate_normal() {
+        let tri = Rc::new(setup());
+        let i = Intersection::new_with_uv(1.0, tri.clone(), 0.45, 0.25);
+        let n = tri.normal_at_hit(point(0.0, 0.0, 0.0), &i).unwrap();
+        assert_eq!(vector(-0.5547, 0.83205, 0.0), n);
+    }
+
+    #[test]
+    fn preparing_normal_on_smooth_triangle() {
+        let tri = Rc::new(setup());
+        let r = Ray {
+            origin: point(-0.2, 0.3, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = intersect(tri, &r).unwrap();
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        assert_eq!(vector(-0.5547, 0.83205, 0.0), comps.normalv);
+    }
+
+    #[test]
+    fn smooth_triangle_has_bounding_box() {
+        let b = setup().bounds();
+        assert_eq!(point(-1.0, 0.0, 0.0), b.get_min());
+        assert_eq!(point(1.0, 1.0, 0.0), b.get_max());
+    }
+}
@@ -1,26 +1,46 @@
 use crate::{
+    bounds::BoundingBox,
     color::Color,
+    intersections::{Intersection, Intersections},
     material::Material,
     matrix::{Matrix4, MatrixError},
-    shape::Shape,
-    tuple::{normalize, point, Tuple, TupleT},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{dot, point, Tuple},
+    utils::eq_with_eps,
 };
-use std::any::Any;
+use std::{any::Any, rc::Rc};
 use uuid::Uuid;
 
 // For simplicity, Sphere currently has radius 1 and center on (0, 0, 0)
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Sphere {
     id: Uuid,
     transform: Matrix4,
     material: Material,
 }
 
+/// Sphere made of glass - used in tests of refraction
+#[cfg(test)]
+pub(crate) fn glass_sphere() -> Sphere {
+    let mut s = Sphere::default();
+    s.set_material(Material {
+        transparency: 1.0,
+        refractive_index: 1.5,
+        ..Default::default()
+    });
+    s
+}
+
 impl Shape for Sphere {
     fn as_any(&self) -> &dyn Any {
         self
     }
 
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Sphere
+    }
+
     fn set_transform(&mut self, transform: Matrix4) {
         self.transform = transform;
     }
@@ -29,17 +49,32 @@ impl Shape for Sphere {
         self.transform
     }
 
-    /// Normal at point on sphere is a vector perpendicular to the surface - it's the normal
-    fn normal_at(&self, world_point: Tuple) -> Result<Tuple, MatrixError> {
-        // converting point from world space to object space by multiplying point by inverse of
-        // transformation matrix
-        let object_point = self.transform.inverse()? * world_point;
-        let object_normal = object_point - point(0.0, 0.0, 0.0);
-        let mut world_normal = self.transform.inverse()?.transpose()? * object_normal;
-        // hack - in order to avoid multiplication and inversing a submatrix of transformation,
-        // parameter w is set by hand to 0; otherwise some transformation might corrupt that value
-        world_normal.set_w(0.0);
-        Ok(normalize(&world_normal))
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        // Vector from the sphere's center to the ray origin
+        let sphere_to_ray = ray.origin - point(0.0, 0.0, 0.0);
+        let a = dot(&ray.direction, &ray.direction);
+        let b = 2.0 * dot(&ray.direction, &sphere_to_ray);
+        let c = dot(&sphere_to_ray, &sphere_to_ray) - 1.0;
+        let discriminant = (b * b) - (4.0 * a * c);
+
+        let mut xs = Intersections::new();
+        if eq_with_eps(discriminant, 0.0) || discriminant > 0.0 {
+            let sqrt_discriminant = discriminant.sqrt();
+            let t1 = (-b - sqrt_discriminant) / (2.0 * a);
+            let t2 = (-b + sqrt_discriminant) / (2.0 * a);
+            xs.add(Intersection::new(t1, self.clone()));
+            xs.add(Intersection::new(t2, self));
+        }
+        Ok(xs)
+    }
+
+    /// Normal of unit sphere is simply a vector from its center to the point
+    fn local_normal_at(&self, object_point: Tuple) -> Tuple {
+        object_point - point(0.0, 0.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0))
     }
 
     fn set_material(&mut self, m: Material) {
@@ -90,10 +125,9 @@ mod tests {
         intersections::intersect,
         ray::Ray,
         transformations::{rotation_z, scaling, translation},
-        tuple::vector,
-        utils::{eq_with_eps, PI},
+        tuple::{normalize, vector},
+        utils::PI,
     };
-    use std::rc::Rc;
 
     #[test]
     fn ray_intersects_sphere_at_two_points() {
@@ -165,12 +199,18 @@ mod tests {
             direction: vector(0.0, 0.0, 1.0),
         };
         let s = Sphere::default();
-        let xs = intersect(Rc::new(s), &r).unwrap();
+        let xs = intersect(Rc::new(s.clone()), &r).unwrap();
         assert_eq!(2, xs.len());
         assert_eq!(s.get_id(), xs[0].object.borrow().get_id());
         assert_eq!(s.get_id(), xs[1].object.borrow().get_id());
     }
 
+    #[test]
+    fn sphere_has_sphere_shape_type() {
+        let s = Sphere::default();
+        assert_eq!(ShapeType::Sphere, s.get_shape_type());
+    }
+
     #[test]
     fn spheres_default_transformation() {
         let s = Sphere::default();
@@ -298,7 +338,23 @@ mod tests {
             ambient: 1.0,
             ..Default::default()
         };
-        s.set_material(m);
+        s.set_material(m.clone());
         assert_eq!(m, s.material);
     }
+
+    #[test]
+    fn sphere_has_bounding_box() {
+        let s = Sphere::default();
+        let b = s.bounds();
+        assert_eq!(point(-1.0, -1.0, -1.0), b.get_min());
+        assert_eq!(point(1.0, 1.0, 1.0), b.get_max());
+    }
+
+    #[test]
+    fn helper_for_producing_sphere_with_glassy_material() {
+        let s = glass_sphere();
+        assert_eq!(Matrix4::identity_matrix(), s.get_transform());
+        assert!(eq_with_eps(1.0, s.get_material().transparency));
+        assert!(eq_with_eps(1.5, s.get_material().refractive_index));
+    }
 }
@@ -0,0 +1,198 @@
+use crate::{canvas::Canvas, color::Color};
+
+/// Operators compressing unbounded scene colors into displayable range [0, 1]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ToneMapping {
+    /// Everything above 1 is cut off - highlights blow out to white
+    Clamp,
+    /// c / (1 + c) per channel - never reaches 1, so highlights keep their detail
+    Reinhard,
+    /// Filmic curve approximating ACES reference rendering transform (after Krzysztof Narkowicz),
+    /// with toe in shadows and soft shoulder in highlights
+    AcesFilmic,
+}
+
+/// Encoding of display-referred linear values before they are quantized
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TransferFunction {
+    /// Values are written as they are
+    Linear,
+    /// Piecewise gamma curve of sRGB, expected by image viewers for 8 and 16 bit images
+    Srgb,
+}
+
+impl ToneMapping {
+    pub fn map(&self, value: f64) -> f64 {
+        let value = value.max(0.0);
+        match self {
+            ToneMapping::Clamp => value.min(1.0),
+            // Written so that infinity maps to 1 instead of inf / inf
+            ToneMapping::Reinhard => 1.0 - 1.0 / (1.0 + value),
+            ToneMapping::AcesFilmic => {
+                // Curve is flat at 1 long before squares of the value overflow
+                let value = value.min(1e6);
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                ((value * (a * value + b)) / (value * (c * value + d) + e)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+impl TransferFunction {
+    pub fn encode(&self, value: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => value,
+            TransferFunction::Srgb => {
+                if value <= 0.0031308 {
+                    12.92 * value
+                } else {
+                    1.055 * value.powf(1.0 / 2.4) - 0.055
+                }
+            }
+        }
+    }
+}
+
+/// Stage between the canvas, which holds linear scene colors, and the 8/16 bit encoders: exposure
+/// scales the colors, tone mapping brings them into [0, 1] and transfer function encodes them.
+/// Default transform only clamps, so output matches plain linear export
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OutputTransform {
+    exposure: f64,
+    tone_mapping: ToneMapping,
+    transfer: TransferFunction,
+}
+
+impl OutputTransform {
+    /// `exposure` is given in stops - every stop doubles the brightness
+    pub fn new(
+        exposure: f64,
+        tone_mapping: ToneMapping,
+        transfer: TransferFunction,
+    ) -> OutputTransform {
+        OutputTransform {
+            exposure,
+            tone_mapping,
+            transfer,
+        }
+    }
+
+    pub fn get_exposure(&self) -> f64 {
+        self.exposure
+    }
+
+    pub fn get_tone_mapping(&self) -> ToneMapping {
+        self.tone_mapping
+    }
+
+    pub fn get_transfer(&self) -> TransferFunction {
+        self.transfer
+    }
+
+    pub fn apply(&self, c: Color) -> Color {
+        let gain = 2f64.powf(self.exposure);
+        let channel = |value: f64| self.transfer.encode(self.tone_mapping.map(value * gain));
+        Color::new(
+            channel(c.get_red()),
+            channel(c.get_green()),
+            channel(c.get_blue()),
+        )
+    }
+
+    /// Copy of the canvas with the transform applied to every pixel
+    pub fn apply_canvas(&self, c: &Canvas) -> Canvas {
+        let mut result = c.clone();
+        for pixel in result.as_mut_slice() {
+            *pixel = self.apply(*pixel);
+        }
+        result
+    }
+}
+
+impl Default for OutputTransform {
+    fn default() -> Self {
+        Self::new(0.0, ToneMapping::Clamp, TransferFunction::Linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::eq_with_eps;
+
+    #[test]
+    fn tone_mapping_operators() {
+        let cases = [
+            (ToneMapping::Clamp, -0.5, 0.0),
+            (ToneMapping::Clamp, 0.5, 0.5),
+            (ToneMapping::Clamp, 3.0, 1.0),
+            (ToneMapping::Reinhard, 0.0, 0.0),
+            (ToneMapping::Reinhard, 1.0, 0.5),
+            (ToneMapping::Reinhard, 3.0, 0.75),
+            (ToneMapping::Reinhard, f64::INFINITY, 1.0),
+            (ToneMapping::AcesFilmic, 0.0, 0.0),
+            (ToneMapping::AcesFilmic, 1.0, 0.80380),
+            (ToneMapping::AcesFilmic, 100.0, 1.0),
+            (ToneMapping::AcesFilmic, 1e200, 1.0),
+            (ToneMapping::AcesFilmic, f64::INFINITY, 1.0),
+            (ToneMapping::Clamp, f64::INFINITY, 1.0),
+        ];
+        for (operator, value, expected) in cases.iter() {
+            assert!(eq_with_eps(*expected, operator.map(*value)));
+        }
+    }
+
+    #[test]
+    fn srgb_transfer_function() {
+        let cases = [
+            (0.0, 0.0),
+            (0.002, 0.02584),
+            (0.18, 0.46135),
+            (0.5, 0.73536),
+            (1.0, 1.0),
+        ];
+        for (linear, encoded) in cases.iter() {
+            assert!(eq_with_eps(
+                *encoded,
+                TransferFunction::Srgb.encode(*linear)
+            ));
+            assert!(eq_with_eps(
+                *linear,
+                TransferFunction::Linear.encode(*linear)
+            ));
+        }
+    }
+
+    #[test]
+    fn default_transform_only_clamps() {
+        let t = OutputTransform::default();
+        assert_eq!(
+            Color::new(1.0, 0.5, 0.0),
+            t.apply(Color::new(1.5, 0.5, -0.5))
+        );
+    }
+
+    #[test]
+    fn exposure_is_applied_before_tone_mapping() {
+        let t = OutputTransform::new(1.0, ToneMapping::Reinhard, TransferFunction::Linear);
+        assert_eq!(
+            Color::new(0.5, 0.0, 0.8),
+            t.apply(Color::new(0.5, 0.0, 2.0))
+        );
+        let t = OutputTransform::new(-2.0, ToneMapping::Clamp, TransferFunction::Srgb);
+        assert_eq!(
+            Color::new(0.73536, 1.0, 0.0),
+            t.apply(Color::new(2.0, 8.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn transforming_canvas() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(1, 0, Color::new(3.0, 1.0, 0.0)).unwrap();
+        let t = OutputTransform::new(0.0, ToneMapping::Reinhard, TransferFunction::Linear);
+        let result = t.apply_canvas(&c);
+        assert_eq!(Color::new(0.0, 0.0, 0.0), result.pixel_at(0, 0).unwrap());
+        assert_eq!(Color::new(0.75, 0.5, 0.0), result.pixel_at(1, 0).unwrap());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::matrix::Matrix4;
+use crate::{
+    matrix::Matrix4,
+    tuple::{cross, normalize, Tuple, TupleT},
+};
 
 pub fn translation(x: f64, y: f64, z: f64) -> Matrix4 {
     Matrix4([
@@ -81,6 +84,35 @@ pub fn shearing(x_y: f64, x_z: f64, y_x: f64, y_z: f64, z_x: f64, z_y: f64) -> M
     ])
 }
 
+/// Orients the world relative to the eye. `from` is position of the eye, `to` is the point at
+/// which eye looks and `up` is a vector pointing (approximately) upwards
+pub fn view_transform(from: Tuple, to: Tuple, up: Tuple) -> Matrix4 {
+    let forward = normalize(&(to - from));
+    let left = cross(&forward, &normalize(&up));
+    // up vector doesn't need to be exactly perpendicular to the forward vector - true up is
+    // computed from the other two vectors
+    let true_up = cross(&left, &forward);
+    let orientation = Matrix4([
+        left.get_x(),
+        left.get_y(),
+        left.get_z(),
+        0.0,
+        true_up.get_x(),
+        true_up.get_y(),
+        true_up.get_z(),
+        0.0,
+        -forward.get_x(),
+        -forward.get_y(),
+        -forward.get_z(),
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+    ]);
+    orientation * translation(-from.get_x(), -from.get_y(), -from.get_z())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,4 +267,46 @@ mod tests {
         let trans = c * b * a;
         assert_eq!(point(15.0, 0.0, 7.0), p * trans);
     }
+
+    #[test]
+    fn transformation_matrix_for_default_orientation() {
+        let from = point(0.0, 0.0, 0.0);
+        let to = point(0.0, 0.0, -1.0);
+        let up = vector(0.0, 1.0, 0.0);
+        let t = view_transform(from, to, up);
+        assert_eq!(Matrix4::identity_matrix(), t);
+    }
+
+    #[test]
+    fn view_transformation_matrix_looking_in_positive_z_direction() {
+        let from = point(0.0, 0.0, 0.0);
+        let to = point(0.0, 0.0, 1.0);
+        let up = vector(0.0, 1.0, 0.0);
+        let t = view_transform(from, to, up);
+        assert_eq!(scaling(-1.0, 1.0, -1.0), t);
+    }
+
+    #[test]
+    fn view_transformation_moves_the_world() {
+        let from = point(0.0, 0.0, 8.0);
+        let to = point(0.0, 0.0, 0.0);
+        let up = vector(0.0, 1.0, 0.0);
+        let t = view_transform(from, to, up);
+        assert_eq!(translation(0.0, 0.0, -8.0), t);
+    }
+
+    #[test]
+    fn arbitrary_view_transformation() {
+        let from = point(1.0, 3.0, 2.0);
+        let to = point(4.0, -2.0, 8.0);
+        let up = vector(1.0, 1.0, 0.0);
+        let t = view_transform(from, to, up);
+        assert_eq!(
+            Matrix4([
+                -0.50709, 0.50709, 0.67612, -2.36643, 0.76772, 0.60609, 0.12122, -2.82843,
+                -0.35857, 0.59761, -0.71714, 0.00000, 0.00000, 0.00000, 0.00000, 1.00000,
+            ]),
+            t
+        );
+    }
 }
@@ -0,0 +1,282 @@
+use crate::{
+    bounds::BoundingBox,
+    color::Color,
+    intersections::{Intersection, Intersections},
+    material::Material,
+    matrix::{Matrix4, MatrixError},
+    ray::Ray,
+    shape::{Shape, ShapeType},
+    tuple::{cross, dot, normalize, Tuple},
+    utils::EPSILON,
+};
+use std::{any::Any, rc::Rc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    id: Uuid,
+    transform: Matrix4,
+    material: Material,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    // edges and normal are precomputed, since they are needed for every intersection
+    e1: Tuple,
+    e2: Tuple,
+    normal: Tuple,
+}
+
+/// Intersects triangle given by its first vertex and two edges using Möller–Trumbore algorithm.
+/// Returns distance along the ray and barycentric coordinates (u, v) of the hit
+pub(crate) fn intersect_triangle(
+    p1: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+    ray: &Ray,
+) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = cross(&ray.direction, &e2);
+    let det = dot(&e1, &dir_cross_e2);
+    // ray is parallel to the triangle's plane
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * dot(&p1_to_origin, &dir_cross_e2);
+    // ray misses by passing beyond the p1-p3 edge
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = cross(&p1_to_origin, &e1);
+    let v = f * dot(&ray.direction, &origin_cross_e1);
+    // ray misses by passing beyond p1-p2 or p2-p3 edge
+    if v < 0.0 || (u + v) > 1.0 {
+        return None;
+    }
+
+    Some((f * dot(&e2, &origin_cross_e1), u, v))
+}
+
+impl Triangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Triangle {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Triangle {
+            id: Uuid::new_v4(),
+            transform: Matrix4::identity_matrix(),
+            material: Material::default(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: normalize(&cross(&e2, &e1)),
+        }
+    }
+
+    pub fn get_p1(&self) -> Tuple {
+        self.p1
+    }
+
+    pub fn get_p2(&self) -> Tuple {
+        self.p2
+    }
+
+    pub fn get_p3(&self) -> Tuple {
+        self.p3
+    }
+
+    pub fn get_e1(&self) -> Tuple {
+        self.e1
+    }
+
+    pub fn get_e2(&self) -> Tuple {
+        self.e2
+    }
+
+    pub fn get_normal(&self) -> Tuple {
+        self.normal
+    }
+}
+
+impl Shape for Triangle {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_shape_type(&self) -> ShapeType {
+        ShapeType::Triangle
+    }
+
+    fn set_transform(&mut self, transform: Matrix4) {
+        self.transform = transform;
+    }
+
+    fn get_transform(&self) -> Matrix4 {
+        self.transform
+    }
+
+    fn local_intersect(self: Rc<Self>, ray: &Ray) -> Result<Intersections, MatrixError> {
+        let mut xs = Intersections::new();
+        if let Some((t, u, v)) = intersect_triangle(self.p1, self.e1, self.e2, ray) {
+            xs.add(Intersection::new_with_uv(t, self, u, v));
+        }
+        Ok(xs)
+    }
+
+    /// Triangle is flat, so the normal is the same everywhere
+    fn local_normal_at(&self, _object_point: Tuple) -> Tuple {
+        self.normal
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut b = BoundingBox::default();
+        b.add_point(self.p1);
+        b.add_point(self.p2);
+        b.add_point(self.p3);
+        b
+    }
+
+    fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_color(&mut self, c: Color) {
+        self.material.color = c;
+    }
+
+    fn get_color(&self) -> &Color {
+        &self.material.color
+    }
+
+    fn set_ambient(&mut self, a: f64) {
+        self.material.ambient = a;
+    }
+
+    fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+}
+
+impl PartialEq for Triangle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tuple::{point, vector},
+        utils::eq_with_eps,
+    };
+
+    fn setup() -> Triangle {
+        Triangle::new(
+            point(0.0, 1.0, 0.0),
+            point(-1.0, 0.0, 0.0),
+            point(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_triangle() {
+        let p1 = point(0.0, 1.0, 0.0);
+        let p2 = point(-1.0, 0.0, 0.0);
+        let p3 = point(1.0, 0.0, 0.0);
+        let t = Triangle::new(p1, p2, p3);
+        assert_eq!(ShapeType::Triangle, t.get_shape_type());
+        assert_eq!(p1, t.get_p1());
+        assert_eq!(p2, t.get_p2());
+        assert_eq!(p3, t.get_p3());
+        assert_eq!(vector(-1.0, -1.0, 0.0), t.get_e1());
+        assert_eq!(vector(1.0, -1.0, 0.0), t.get_e2());
+        assert_eq!(vector(0.0, 0.0, -1.0), t.get_normal());
+    }
+
+    #[test]
+    fn finding_normal_on_triangle() {
+        let t = setup();
+        let n1 = t.local_normal_at(point(0.0, 0.5, 0.0));
+        let n2 = t.local_normal_at(point(-0.5, 0.75, 0.0));
+        let n3 = t.local_normal_at(point(0.5, 0.25, 0.0));
+        assert_eq!(t.get_normal(), n1);
+        assert_eq!(t.get_normal(), n2);
+        assert_eq!(t.get_normal(), n3);
+    }
+
+    #[test]
+    fn intersecting_ray_parallel_to_triangle() {
+        let t = Rc::new(setup());
+        let r = Ray {
+            origin: point(0.0, -1.0, -2.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        let xs = t.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p3_edge() {
+        let t = Rc::new(setup());
+        let r = Ray {
+            origin: point(1.0, 1.0, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = t.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p2_edge() {
+        let t = Rc::new(setup());
+        let r = Ray {
+            origin: point(-1.0, 1.0, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = t.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p2_p3_edge() {
+        let t = Rc::new(setup());
+        let r = Ray {
+            origin: point(0.0, -1.0, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = t.local_intersect(&r).unwrap();
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = Rc::new(setup());
+        let r = Ray {
+            origin: point(0.0, 0.5, -2.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = t.local_intersect(&r).unwrap();
+        assert_eq!(1, xs.len());
+        assert!(eq_with_eps(2.0, xs[0].t));
+    }
+
+    #[test]
+    fn triangle_has_bounding_box() {
+        let t = Triangle::new(
+            point(-3.0, 7.0, 2.0),
+            point(6.0, 2.0, -4.0),
+            point(2.0, -1.0, -1.0),
+        );
+        let b = t.bounds();
+        assert_eq!(point(-3.0, -1.0, -4.0), b.get_min());
+        assert_eq!(point(6.0, 7.0, 2.0), b.get_max());
+    }
+}
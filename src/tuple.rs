@@ -179,10 +179,10 @@ pub fn magnitude(v: &Tuple) -> f64 {
 
 pub fn normalize(v: &Tuple) -> Tuple {
     Tuple {
-        x: v.x / magnitude(&v),
-        y: v.y / magnitude(&v),
-        z: v.z / magnitude(&v),
-        w: v.w / magnitude(&v),
+        x: v.x / magnitude(v),
+        y: v.y / magnitude(v),
+        z: v.z / magnitude(v),
+        w: v.w / magnitude(v),
     }
 }
 
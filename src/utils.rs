@@ -1,8 +1,9 @@
 pub const PI: f64 = std::f64::consts::PI;
+// This epsilon is "good enough" for my calculations
+pub const EPSILON: f64 = 0.00001;
 
 pub fn eq_with_eps(a: f64, b: f64) -> bool {
-    // This epsilon is "good enough" for my calculations
-    (a - b).abs() < 0.00001
+    (a - b).abs() < EPSILON
 }
 
 pub fn deg_to_rad(deg: f64) -> f64 {
@@ -1,5 +1,6 @@
 use crate::{
     color::{Color, BLACK},
+    group::Group,
     intersections::{intersect, Computations, Intersections},
     light::PointLight,
     material::{lighting, Material},
@@ -8,13 +9,19 @@ use crate::{
     shape::Shape,
     sphere::Sphere,
     transformations::scaling,
-    tuple::point,
+    tuple::{dot, magnitude, normalize, point, Tuple},
 };
 use std::rc::Rc;
 
+/// Default number of bounces a ray can make between reflective surfaces - without a limit two
+/// parallel mirrors would reflect the ray back and forth forever
+pub const MAX_REFLECTION_DEPTH: usize = 5;
+
 pub struct World {
     pub light: Option<PointLight>,
     pub objects: Vec<Rc<dyn Shape>>,
+    /// Bounding volume hierarchy of the objects, see `build_bvh`
+    pub bvh: Option<Rc<dyn Shape>>,
 }
 
 impl Default for World {
@@ -22,10 +29,14 @@ impl Default for World {
         let mut s1 = Sphere::default();
         s1.set_material(Material {
             color: Color::new(0.8, 1.0, 0.6),
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.7,
             specular: 0.2,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         });
         let mut s2 = Sphere::default();
         s2.set_transform(scaling(0.5, 0.5, 0.5));
@@ -35,6 +46,7 @@ impl Default for World {
                 intensity: Color::new(1.0, 1.0, 1.0),
             }),
             objects: vec![Rc::new(s1), Rc::new(s2)],
+            bvh: None,
         }
     }
 }
@@ -45,6 +57,7 @@ impl World {
         World {
             light: None,
             objects: vec![],
+            bvh: None,
         }
     }
 
@@ -58,26 +71,127 @@ impl World {
         self.objects.get_mut(index)
     }
 
-    pub fn shade_hit(&self, comps: Computations) -> Option<Color> {
+    /// Color of the hit - surface lit by the light source combined with the reflected and
+    /// refracted scene.
+    /// `remaining` tells how many more times the ray may be reflected
+    pub fn shade_hit(&self, comps: Computations, remaining: usize) -> Result<Color, MatrixError> {
         if let Some(light) = self.light {
-            Some(lighting(
-                comps.object.borrow().get_material(),
+            let shadowed = self.is_shadowed(comps.over_point)?;
+            let object = comps.object.borrow();
+            let material = object.get_material();
+            let surface = lighting(
+                &**object,
+                comps.parent_transform,
                 light,
-                comps.point,
+                comps.over_point,
                 comps.eyev,
                 comps.normalv,
-            ))
+                shadowed,
+            )?;
+            let reflected = self.reflected_color(&comps, remaining)?;
+            let refracted = self.refracted_color(&comps, remaining)?;
+            if material.reflective > 0.0 && material.transparency > 0.0 {
+                // surfaces both reflective and transparent (like water or glass) reflect more
+                // and refract less as the viewing angle gets more grazing
+                let reflectance = comps.schlick();
+                Ok(surface + reflected * reflectance + refracted * (1.0 - reflectance))
+            } else {
+                Ok(surface + reflected + refracted)
+            }
         } else {
-            None
+            Ok(BLACK)
+        }
+    }
+
+    /// Casts a ray reflected off the hit and returns its color scaled by the reflectiveness of
+    /// the material. Non-reflective surfaces and rays out of remaining bounces reflect nothing
+    pub fn reflected_color(
+        &self,
+        comps: &Computations,
+        remaining: usize,
+    ) -> Result<Color, MatrixError> {
+        let reflective = comps.object.borrow().get_material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Ok(BLACK);
         }
+        let reflect_ray = Ray {
+            origin: comps.over_point,
+            direction: comps.reflectv,
+        };
+        let color = self.color_at(&reflect_ray, remaining - 1)?;
+        Ok(color * reflective)
+    }
+
+    /// Casts a ray bent by the surface according to Snell's law and returns its color scaled by
+    /// the transparency of the material. Opaque surfaces, rays out of remaining bounces and
+    /// total internal reflection (when the ray can't leave the denser material) give black
+    pub fn refracted_color(
+        &self,
+        comps: &Computations,
+        remaining: usize,
+    ) -> Result<Color, MatrixError> {
+        let transparency = comps.object.borrow().get_material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Ok(BLACK);
+        }
+        let n_ratio = comps.n1 / comps.n2;
+        // cos(theta_i) is the dot product of the two vectors
+        let cos_i = dot(&comps.eyev, &comps.normalv);
+        // sin(theta_t)^2 via trigonometric identity
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        if sin2_t > 1.0 {
+            return Ok(BLACK);
+        }
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let refract_ray = Ray {
+            origin: comps.under_point,
+            direction: comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio,
+        };
+        let color = self.color_at(&refract_ray, remaining - 1)?;
+        Ok(color * transparency)
+    }
+
+    /// Casts a ray from the point towards the light source - if anything is hit before reaching
+    /// the light, the point is in shadow. Without light source every point is in shadow
+    pub fn is_shadowed(&self, point: Tuple) -> Result<bool, MatrixError> {
+        if let Some(light) = self.light {
+            let v = light.position - point;
+            let distance = magnitude(&v);
+            let ray = Ray {
+                origin: point,
+                direction: normalize(&v),
+            };
+            let mut intersections = Intersections::new();
+            self.intersect_world(&ray, &mut intersections)?;
+            Ok(matches!(intersections.hit(), Some(hit) if hit.t < distance))
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Puts all objects into a single group and divides it into a bounding volume hierarchy -
+    /// groups with at least `threshold` children are split into subgroups, so that a ray skips
+    /// every subgroup whose bounds it misses. `objects` are left as they are, but rays are cast
+    /// against the hierarchy instead of them, so it has to be built again after objects change
+    pub fn build_bvh(&mut self, threshold: usize) {
+        let mut root = Group::default();
+        for o in self.objects.iter() {
+            root.add_child(o.clone());
+        }
+        root.divide(threshold);
+        self.bvh = Some(Rc::new(root));
     }
 
     fn intersect_world(
-        &mut self,
+        &self,
         ray: &Ray,
         intersections: &mut Intersections,
     ) -> Result<(), MatrixError> {
-        for o in self.objects.iter() {
+        let objects = match &self.bvh {
+            Some(bvh) => std::slice::from_ref(bvh),
+            None => &self.objects,
+        };
+        for o in objects.iter() {
             let mut intersection = intersect(o.clone(), ray)?;
             intersections.append(&mut intersection);
         }
@@ -86,13 +200,12 @@ impl World {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn color_at(&mut self, ray: &Ray) -> Result<Color, MatrixError> {
+    pub fn color_at(&self, ray: &Ray, remaining: usize) -> Result<Color, MatrixError> {
         let mut intersections = Intersections::new();
         self.intersect_world(ray, &mut intersections)?;
-        if let Some(intersection) = intersections.hit() {
-            let comps = Computations::prepare_computation(intersection.clone(), *ray)?;
-            Ok(self.shade_hit(comps).unwrap_or(BLACK))
+        if let Some(intersection) = intersections.hit().cloned() {
+            let comps = Computations::prepare_computation(intersection, *ray, &intersections)?;
+            self.shade_hit(comps, remaining)
         } else {
             Ok(BLACK)
         }
@@ -102,8 +215,10 @@ impl World {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{intersections::Intersection, tuple::vector, utils::eq_with_eps};
-    use std::{cell::RefCell, rc::Rc};
+    use crate::{
+        intersections::Intersection, plane::Plane, transformations::translation, tuple::vector,
+        utils::eq_with_eps,
+    };
 
     // #[test]
     // fn creating_world() {
@@ -123,10 +238,14 @@ mod tests {
         let mut s1 = Sphere::default();
         s1.set_material(Material {
             color: Color::new(0.8, 1.0, 0.6),
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.7,
             specular: 0.2,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         });
         let mut s2 = Sphere::default();
         s2.set_transform(scaling(0.5, 0.5, 0.5));
@@ -146,7 +265,7 @@ mod tests {
 
     #[test]
     fn intersecting_world_with_ray() {
-        let mut w = World::default();
+        let w = World::default();
         let r = Ray {
             origin: point(0.0, 0.0, -5.0),
             direction: vector(0.0, 0.0, 1.0),
@@ -167,13 +286,11 @@ mod tests {
             origin: point(0.0, 0.0, -5.0),
             direction: vector(0.0, 0.0, 1.0),
         };
-        let shape: Sphere = *w.objects[0].as_any().downcast_ref::<Sphere>().unwrap();
-        let i = Intersection {
-            t: 4.0,
-            object: RefCell::new(Rc::new(shape)),
-        };
-        let comps = Computations::prepare_computation(i, r).unwrap();
-        let c = w.shade_hit(comps).unwrap();
+        let shape = w.objects[0].clone();
+        let i = Intersection::new(4.0, shape);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
         assert_eq!(Color::new(0.38066, 0.47583, 0.2855), c);
     }
 
@@ -191,35 +308,33 @@ mod tests {
             origin: point(0.0, 0.0, 0.0),
             direction: vector(0.0, 0.0, 1.0),
         };
-        let shape: Sphere = *w.objects[1].as_any().downcast_ref::<Sphere>().unwrap();
-        let i = Intersection {
-            t: 0.5,
-            object: RefCell::new(Rc::new(shape)),
-        };
-        let comps = Computations::prepare_computation(i, r).unwrap();
-        let c = w.shade_hit(comps).unwrap();
+        let shape = w.objects[1].clone();
+        let i = Intersection::new(0.5, shape);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
         assert_eq!(Color::new(00.90498, 0.90498, 0.90498), c);
     }
 
     #[test]
     fn color_when_ray_misses() {
-        let mut w = World::default();
+        let w = World::default();
         let r = Ray {
             origin: point(0.0, 0.0, -5.0),
             direction: vector(0.0, 1.0, 0.0),
         };
-        let c = w.color_at(&r).unwrap();
+        let c = w.color_at(&r, MAX_REFLECTION_DEPTH).unwrap();
         assert_eq!(BLACK, c);
     }
 
     #[test]
     fn color_when_ray_hits() {
-        let mut w = World::default();
+        let w = World::default();
         let r = Ray {
             origin: point(0.0, 0.0, -5.0),
             direction: vector(0.0, 0.0, 1.0),
         };
-        let c = w.color_at(&r).unwrap();
+        let c = w.color_at(&r, MAX_REFLECTION_DEPTH).unwrap();
         assert_eq!(Color::new(0.38066, 0.47583, 0.2855), c);
     }
 
@@ -235,7 +350,350 @@ mod tests {
             origin: point(0.0, 0.0, 0.75),
             direction: vector(0.0, 0.0, -1.0),
         };
-        let c = w.color_at(&r).unwrap();
+        let c = w.color_at(&r, MAX_REFLECTION_DEPTH).unwrap();
         assert_eq!(output_color, c);
     }
+
+    #[test]
+    fn there_is_no_shadow_when_nothing_is_collinear_with_point_and_light() {
+        let w = World::default();
+        let p = point(0.0, 10.0, 0.0);
+        assert!(!w.is_shadowed(p).unwrap());
+    }
+
+    #[test]
+    fn shadow_when_object_is_between_point_and_light() {
+        let w = World::default();
+        let p = point(10.0, 10.0, 10.0);
+        assert!(w.is_shadowed(p).unwrap());
+    }
+
+    #[test]
+    fn there_is_no_shadow_when_object_is_behind_light() {
+        let w = World::default();
+        let p = point(-20.0, -20.0, -20.0);
+        assert!(!w.is_shadowed(p).unwrap());
+    }
+
+    #[test]
+    fn there_is_no_shadow_when_object_is_behind_point() {
+        let w = World::default();
+        let p = point(-2.0, -2.0, -2.0);
+        assert!(!w.is_shadowed(p).unwrap());
+    }
+
+    #[test]
+    fn shade_hit_is_given_intersection_in_shadow() {
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0.0, 0.0, 10.0));
+        let w = World {
+            light: Some(PointLight {
+                position: point(0.0, 0.0, -10.0),
+                intensity: Color::new(1.0, 1.0, 1.0),
+            }),
+            objects: vec![Rc::new(s1), Rc::new(s2.clone())],
+            bvh: None,
+        };
+        let r = Ray {
+            origin: point(0.0, 0.0, 5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let i = Intersection::new(4.0, Rc::new(s2));
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(Color::new(0.1, 0.1, 0.1), c);
+    }
+
+    #[test]
+    fn building_bvh_keeps_world_colors() {
+        let mut w = World::default();
+        let mut p = Plane::default();
+        p.set_transform(translation(0.0, -1.0, 0.0));
+        let p: Rc<dyn Shape> = Rc::new(p);
+        w.objects.push(p.clone());
+        let rays = [
+            (point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0)),
+            (point(0.0, 0.0, 0.75), vector(0.0, 0.0, -1.0)),
+            (point(0.0, 5.0, -5.0), vector(0.0, -1.0, 1.0)),
+            (point(0.0, 0.0, -5.0), vector(0.0, 1.0, 0.0)),
+        ];
+        let expected = rays
+            .iter()
+            .map(|(origin, direction)| {
+                w.color_at(
+                    &Ray {
+                        origin: *origin,
+                        direction: normalize(direction),
+                    },
+                    MAX_REFLECTION_DEPTH,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        w.build_bvh(1);
+        assert_eq!(3, w.objects.len());
+        assert!(Rc::ptr_eq(&p, w.get_rco(2).unwrap()));
+        assert!(w.bvh.is_some());
+        for ((origin, direction), color) in rays.iter().zip(expected.iter()) {
+            let c = w
+                .color_at(
+                    &Ray {
+                        origin: *origin,
+                        direction: normalize(direction),
+                    },
+                    MAX_REFLECTION_DEPTH,
+                )
+                .unwrap();
+            assert_eq!(*color, c);
+        }
+    }
+
+    /// Default world lit from above, like the scenes in examples
+    fn world_lit_from_above() -> World {
+        World {
+            light: Some(PointLight {
+                position: point(-10.0, 10.0, -10.0),
+                intensity: Color::new(1.0, 1.0, 1.0),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn reflective_floor() -> Rc<dyn Shape> {
+        let mut p = Plane::default();
+        p.set_transform(translation(0.0, -1.0, 0.0));
+        p.set_material(Material {
+            reflective: 0.5,
+            ..Default::default()
+        });
+        Rc::new(p)
+    }
+
+    #[test]
+    fn reflected_color_for_nonreflective_material() {
+        let mut w = world_lit_from_above();
+        let mut s2 = Sphere::default();
+        s2.set_transform(scaling(0.5, 0.5, 0.5));
+        s2.set_ambient(1.0);
+        let s2: Rc<dyn Shape> = Rc::new(s2);
+        w.objects[1] = s2.clone();
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let i = Intersection::new(1.0, s2);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.reflected_color(&comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(BLACK, c);
+    }
+
+    #[test]
+    fn reflected_color_for_reflective_material() {
+        let mut w = world_lit_from_above();
+        let floor = reflective_floor();
+        w.objects.push(floor.clone());
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, -3.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let i = Intersection::new(2.0f64.sqrt(), floor);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.reflected_color(&comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(Color::new(0.19033, 0.23791, 0.14274), c);
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        let mut w = world_lit_from_above();
+        let floor = reflective_floor();
+        w.objects.push(floor.clone());
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, -3.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let i = Intersection::new(2.0f64.sqrt(), floor);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(Color::new(0.87676, 0.92434, 0.82917), c);
+    }
+
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces() {
+        let mirror = Material {
+            reflective: 1.0,
+            ..Default::default()
+        };
+        let mut lower = Plane::default();
+        lower.set_transform(translation(0.0, -1.0, 0.0));
+        lower.set_material(mirror.clone());
+        let mut upper = Plane::default();
+        upper.set_transform(translation(0.0, 1.0, 0.0));
+        upper.set_material(mirror);
+        let w = World {
+            light: Some(PointLight {
+                position: point(0.0, 0.0, 0.0),
+                intensity: Color::new(1.0, 1.0, 1.0),
+            }),
+            objects: vec![Rc::new(lower), Rc::new(upper)],
+            bvh: None,
+        };
+        let r = Ray {
+            origin: point(0.0, 0.0, 0.0),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        assert!(w.color_at(&r, MAX_REFLECTION_DEPTH).is_ok());
+    }
+
+    #[test]
+    fn reflected_color_at_maximum_recursive_depth() {
+        let mut w = world_lit_from_above();
+        let floor = reflective_floor();
+        w.objects.push(floor.clone());
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, -3.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let i = Intersection::new(2.0f64.sqrt(), floor);
+        let comps =
+            Computations::prepare_computation(i.clone(), r, &Intersections::from(vec![i])).unwrap();
+        let c = w.reflected_color(&comps, 0).unwrap();
+        assert_eq!(BLACK, c);
+    }
+
+    #[test]
+    fn refracted_color_with_opaque_surface() {
+        let w = World::default();
+        let shape = w.objects[0].clone();
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(4.0, shape.clone()),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        let c = w.refracted_color(&comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(BLACK, c);
+    }
+
+    /// Default world with its outer sphere made of glass
+    fn world_with_glass_sphere() -> World {
+        let mut w = World::default();
+        let mut s1 = Sphere::default();
+        s1.set_material(Material {
+            transparency: 1.0,
+            refractive_index: 1.5,
+            ..w.objects[0].get_material().clone()
+        });
+        w.objects[0] = Rc::new(s1);
+        w
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_recursive_depth() {
+        let w = world_with_glass_sphere();
+        let shape = w.objects[0].clone();
+        let r = Ray {
+            origin: point(0.0, 0.0, -5.0),
+            direction: vector(0.0, 0.0, 1.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(4.0, shape.clone()),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        let c = w.refracted_color(&comps, 0).unwrap();
+        assert_eq!(BLACK, c);
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let w = world_with_glass_sphere();
+        let shape = w.objects[0].clone();
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, half_sqrt),
+            direction: vector(0.0, 1.0, 0.0),
+        };
+        let xs = Intersections::from(vec![
+            Intersection::new(-half_sqrt, shape.clone()),
+            Intersection::new(half_sqrt, shape),
+        ]);
+        // inside the sphere, so the second intersection is the one that matters
+        let comps = Computations::prepare_computation(xs[1].clone(), r, &xs).unwrap();
+        let c = w.refracted_color(&comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(BLACK, c);
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let mut w = world_lit_from_above();
+        let mut floor = Plane::default();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.set_material(Material {
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Default::default()
+        });
+        let floor: Rc<dyn Shape> = Rc::new(floor);
+        let mut ball = Sphere::default();
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        ball.set_material(Material {
+            color: Color::new(1.0, 0.0, 0.0),
+            ambient: 0.5,
+            ..Default::default()
+        });
+        w.objects.push(floor.clone());
+        w.objects.push(Rc::new(ball));
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, -3.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let xs = Intersections::from(vec![Intersection::new(2.0f64.sqrt(), floor)]);
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(Color::new(0.93642, 0.68642, 0.68642), c);
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        let mut w = world_lit_from_above();
+        let mut floor = Plane::default();
+        floor.set_transform(translation(0.0, -1.0, 0.0));
+        floor.set_material(Material {
+            reflective: 0.5,
+            transparency: 0.5,
+            refractive_index: 1.5,
+            ..Default::default()
+        });
+        let floor: Rc<dyn Shape> = Rc::new(floor);
+        let mut ball = Sphere::default();
+        ball.set_transform(translation(0.0, -3.5, -0.5));
+        ball.set_material(Material {
+            color: Color::new(1.0, 0.0, 0.0),
+            ambient: 0.5,
+            ..Default::default()
+        });
+        w.objects.push(floor.clone());
+        w.objects.push(Rc::new(ball));
+        let half_sqrt = 2.0f64.sqrt() / 2.0;
+        let r = Ray {
+            origin: point(0.0, 0.0, -3.0),
+            direction: vector(0.0, -half_sqrt, half_sqrt),
+        };
+        let xs = Intersections::from(vec![Intersection::new(2.0f64.sqrt(), floor)]);
+        let comps = Computations::prepare_computation(xs[0].clone(), r, &xs).unwrap();
+        let c = w.shade_hit(comps, MAX_REFLECTION_DEPTH).unwrap();
+        assert_eq!(Color::new(0.93391, 0.69643, 0.69243), c);
+    }
 }